@@ -0,0 +1,164 @@
+//! Single AES rounds, as used by AES-based hashes.
+//!
+//! These match the semantics of the x86 `aesenc` and `aesdec` instructions,
+//! with the 128-bit state and round key given as `u128`s whose little-endian
+//! bytes are the bytes of the AES state in memory order.  The hardware
+//! instructions are used when the CPU supports them, and a portable software
+//! implementation is used otherwise.
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+/// One full AES encryption round: ShiftRows, SubBytes, MixColumns, and then
+/// xoring in the round key.
+pub fn aesenc(state: u128, key: u128) -> u128 {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if std::is_x86_feature_detected!("aes") {
+        return unsafe { aesenc_hw(state, key) };
+    }
+
+    aesenc_soft(state, key)
+}
+
+/// One full AES decryption round: InvShiftRows, InvSubBytes, InvMixColumns,
+/// and then xoring in the round key.
+pub fn aesdec(state: u128, key: u128) -> u128 {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if std::is_x86_feature_detected!("aes") {
+        return unsafe { aesdec_hw(state, key) };
+    }
+
+    aesdec_soft(state, key)
+}
+
+/// `aesenc()` using the x86 AES-NI instruction.
+///
+/// # Safety
+///
+/// The CPU must support the `aes` target feature.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "aes")]
+pub unsafe fn aesenc_hw(state: u128, key: u128) -> u128 {
+    let state = _mm_loadu_si128(state.to_le_bytes().as_ptr().cast());
+    let key = _mm_loadu_si128(key.to_le_bytes().as_ptr().cast());
+
+    let mut out = [0u8; 16];
+    _mm_storeu_si128(out.as_mut_ptr().cast(), _mm_aesenc_si128(state, key));
+    u128::from_le_bytes(out)
+}
+
+/// `aesdec()` using the x86 AES-NI instruction.
+///
+/// # Safety
+///
+/// The CPU must support the `aes` target feature.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "aes")]
+pub unsafe fn aesdec_hw(state: u128, key: u128) -> u128 {
+    let state = _mm_loadu_si128(state.to_le_bytes().as_ptr().cast());
+    let key = _mm_loadu_si128(key.to_le_bytes().as_ptr().cast());
+
+    let mut out = [0u8; 16];
+    _mm_storeu_si128(out.as_mut_ptr().cast(), _mm_aesdec_si128(state, key));
+    u128::from_le_bytes(out)
+}
+
+/// `aesenc()` in portable software.
+///
+/// This makes no attempt to be constant time, since it's only meant for
+/// analyzing mixing.
+pub fn aesenc_soft(state: u128, key: u128) -> u128 {
+    let s = state.to_le_bytes();
+
+    // The state is column-major, so byte `col * 4 + row` is at the given row
+    // and column.  ShiftRows rotates row `n` left by `n` columns.
+    let mut t = [0u8; 16];
+    for col in 0..4 {
+        for row in 0..4 {
+            t[col * 4 + row] = SBOX[s[((col + row) % 4) * 4 + row] as usize];
+        }
+    }
+
+    let mut out = [0u8; 16];
+    for col in 0..4 {
+        let [a0, a1, a2, a3] = [t[col * 4], t[col * 4 + 1], t[col * 4 + 2], t[col * 4 + 3]];
+        out[col * 4] = gmul(a0, 2) ^ gmul(a1, 3) ^ a2 ^ a3;
+        out[col * 4 + 1] = a0 ^ gmul(a1, 2) ^ gmul(a2, 3) ^ a3;
+        out[col * 4 + 2] = a0 ^ a1 ^ gmul(a2, 2) ^ gmul(a3, 3);
+        out[col * 4 + 3] = gmul(a0, 3) ^ a1 ^ a2 ^ gmul(a3, 2);
+    }
+
+    u128::from_le_bytes(out) ^ key
+}
+
+/// `aesdec()` in portable software.
+///
+/// This makes no attempt to be constant time, since it's only meant for
+/// analyzing mixing.
+pub fn aesdec_soft(state: u128, key: u128) -> u128 {
+    let s = state.to_le_bytes();
+
+    // InvShiftRows rotates row `n` right by `n` columns.
+    let mut t = [0u8; 16];
+    for col in 0..4 {
+        for row in 0..4 {
+            t[col * 4 + row] = INV_SBOX[s[((col + 4 - row) % 4) * 4 + row] as usize];
+        }
+    }
+
+    let mut out = [0u8; 16];
+    for col in 0..4 {
+        let [a0, a1, a2, a3] = [t[col * 4], t[col * 4 + 1], t[col * 4 + 2], t[col * 4 + 3]];
+        out[col * 4] = gmul(a0, 14) ^ gmul(a1, 11) ^ gmul(a2, 13) ^ gmul(a3, 9);
+        out[col * 4 + 1] = gmul(a0, 9) ^ gmul(a1, 14) ^ gmul(a2, 11) ^ gmul(a3, 13);
+        out[col * 4 + 2] = gmul(a0, 13) ^ gmul(a1, 9) ^ gmul(a2, 14) ^ gmul(a3, 11);
+        out[col * 4 + 3] = gmul(a0, 11) ^ gmul(a1, 13) ^ gmul(a2, 9) ^ gmul(a3, 14);
+    }
+
+    u128::from_le_bytes(out) ^ key
+}
+
+/// Multiplication in AES's GF(2^8).
+fn gmul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        a = (a << 1) ^ if a & 0x80 != 0 { 0x1b } else { 0 };
+        b >>= 1;
+    }
+    product
+}
+
+#[rustfmt::skip]
+const SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+const INV_SBOX: [u8; 256] = {
+    let mut inv = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        inv[SBOX[i] as usize] = i as u8;
+        i += 1;
+    }
+    inv
+};
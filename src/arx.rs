@@ -0,0 +1,327 @@
+//! A small text format for describing ARX (and multiply) mixers, so that new
+//! schedules can be tried without recompiling.
+//!
+//! A spec is a list of lines, with `#` starting a comment:
+//!
+//! ```text
+//! name Goodhart mixer     # Optional, the rest of the line.
+//! lanes 2                 # The number of lanes, named `a`, `b`, `c`, ...
+//! width 64                # The lane width in bits: 8, 16, 32, or 64.
+//! digest 128              # Optional, in bits.  Defaults to the full state.
+//! rounds 12               # Optional default round count.  Defaults to the
+//!                         # number of `round` blocks.
+//!
+//! round                   # Starts a round.  The rounds cycle, so the
+//!     add a b             # round count can exceed the number of blocks.
+//!     add a 1
+//!     rotl b 12
+//!     xor b a
+//! round
+//!     ...
+//! ```
+//!
+//! The operations are:
+//!
+//! - `add x y`, `xor x y`, `mul x y`: `x = x op y`, where `y` is a lane or a
+//!   constant (decimal or `0x` hex).  `add` and `mul` wrap.
+//! - `rotl x N`, `rotr x N`: rotate lane `x` by the constant `N`.
+//! - `swap x y`: swap two lanes.
+//! - `repeat N` ... `end`: repeat the enclosed operations `N` times.
+//!
+//! The state is read from and written to the mixer's bytes as little-endian
+//! lanes, in order.
+
+use std::path::Path;
+
+/// A parsed mixer spec.
+#[derive(Debug, Clone)]
+pub struct Spec {
+    pub name: Option<String>,
+    pub lane_count: usize,
+    /// In bits.
+    pub width: u32,
+    /// In bits.
+    pub digest_bits: usize,
+    pub default_rounds: usize,
+
+    /// The operations of each round, with any `repeat` blocks expanded.
+    rounds: Vec<Vec<Op>>,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Operand {
+    Lane(usize),
+    Const(u64),
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Op {
+    Add(usize, Operand),
+    Xor(usize, Operand),
+    Mul(usize, Operand),
+    Rotl(usize, u32),
+    Swap(usize, usize),
+}
+
+impl Spec {
+    /// Parses a spec from its text.
+    ///
+    /// Errors are returned as a message including the offending line number.
+    pub fn parse(text: &str) -> Result<Spec, String> {
+        let mut name = None;
+        let mut lane_count = None;
+        let mut width = None;
+        let mut digest_bits = None;
+        let mut default_rounds = None;
+        let mut rounds: Vec<Vec<Op>> = Vec::new();
+
+        // The operations and repeat count of each open `repeat` block.
+        let mut repeat_stack: Vec<(Vec<Op>, usize)> = Vec::new();
+
+        for (line_idx, line) in text.lines().enumerate() {
+            let err = |msg: String| format!("line {}: {}", line_idx + 1, msg);
+
+            let line = line.split('#').next().unwrap().trim();
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let Some(&keyword) = words.first() else {
+                continue;
+            };
+            let args = &words[1..];
+
+            // Header directives.
+            match keyword {
+                "name" => {
+                    name = Some(line["name".len()..].trim().to_string());
+                    continue;
+                }
+                "lanes" | "width" | "digest" | "rounds" => {
+                    if !rounds.is_empty() {
+                        return Err(err(format!("`{}` must come before any rounds", keyword)));
+                    }
+                    let [value] = args else {
+                        return Err(err(format!("`{}` expects one number", keyword)));
+                    };
+                    let value = parse_number(value).map_err(&err)? as usize;
+                    match keyword {
+                        "lanes" if (1..=26).contains(&value) => lane_count = Some(value),
+                        "width" if [8, 16, 32, 64].contains(&value) => width = Some(value as u32),
+                        "digest" if value > 0 && value.is_multiple_of(8) => {
+                            digest_bits = Some(value)
+                        }
+                        "rounds" => default_rounds = Some(value),
+                        _ => return Err(err(format!("invalid `{}` value {}", keyword, value))),
+                    }
+                    continue;
+                }
+                "round" => {
+                    if !args.is_empty() {
+                        return Err(err("`round` takes no arguments".into()));
+                    }
+                    if !repeat_stack.is_empty() {
+                        return Err(err("`repeat` block not closed with `end`".into()));
+                    }
+                    if lane_count.is_none() || width.is_none() {
+                        return Err(err("`lanes` and `width` must come before any rounds".into()));
+                    }
+                    rounds.push(Vec::new());
+                    continue;
+                }
+                _ => {}
+            }
+
+            // Everything else is inside a round.
+            let lane_count = lane_count.unwrap_or(0);
+            if rounds.is_empty() {
+                return Err(err(format!("`{}` outside of a round", keyword)));
+            }
+            let lane = |s: &str| -> Result<usize, String> {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c @ 'a'..='z'), None) if ((c as u8 - b'a') as usize) < lane_count => {
+                        Ok((c as u8 - b'a') as usize)
+                    }
+                    _ => Err(err(format!("`{}` is not a lane", s))),
+                }
+            };
+            let operand = |s: &str| -> Result<Operand, String> {
+                if s.starts_with(|c: char| c.is_ascii_digit()) {
+                    parse_number(s).map(Operand::Const).map_err(&err)
+                } else {
+                    lane(s).map(Operand::Lane)
+                }
+            };
+
+            let op = match (keyword, args) {
+                ("add", [x, y]) => Op::Add(lane(x)?, operand(y)?),
+                ("xor", [x, y]) => Op::Xor(lane(x)?, operand(y)?),
+                ("mul", [x, y]) => Op::Mul(lane(x)?, operand(y)?),
+                ("rotl", [x, n]) | ("rotr", [x, n]) => {
+                    let width = width.unwrap();
+                    let n = (parse_number(n).map_err(&err)? % width as u64) as u32;
+                    let n = if keyword == "rotl" {
+                        n
+                    } else {
+                        (width - n) % width
+                    };
+                    Op::Rotl(lane(x)?, n)
+                }
+                ("swap", [x, y]) => Op::Swap(lane(x)?, lane(y)?),
+                ("repeat", [n]) => {
+                    let n = parse_number(n).map_err(&err)? as usize;
+                    repeat_stack.push((Vec::new(), n));
+                    continue;
+                }
+                ("end", []) => {
+                    let Some((ops, n)) = repeat_stack.pop() else {
+                        return Err(err("`end` without `repeat`".into()));
+                    };
+                    let target = match repeat_stack.last_mut() {
+                        Some((outer, _)) => outer,
+                        None => rounds.last_mut().unwrap(),
+                    };
+                    for _ in 0..n {
+                        target.extend_from_slice(&ops);
+                    }
+                    continue;
+                }
+                ("add" | "xor" | "mul" | "rotl" | "rotr" | "swap" | "repeat" | "end", _) => {
+                    return Err(err(format!("wrong number of arguments to `{}`", keyword)));
+                }
+                _ => return Err(err(format!("unknown operation `{}`", keyword))),
+            };
+
+            match repeat_stack.last_mut() {
+                Some((ops, _)) => ops.push(op),
+                None => rounds.last_mut().unwrap().push(op),
+            }
+        }
+
+        if !repeat_stack.is_empty() {
+            return Err("`repeat` block not closed with `end`".into());
+        }
+        if rounds.is_empty() {
+            return Err("no rounds".into());
+        }
+
+        let lane_count = lane_count.unwrap();
+        let width = width.unwrap();
+        let state_bits = lane_count * width as usize;
+        let digest_bits = digest_bits.unwrap_or(state_bits);
+        if digest_bits > state_bits {
+            return Err(format!(
+                "digest of {} bits is larger than the {}-bit state",
+                digest_bits, state_bits
+            ));
+        }
+
+        Ok(Spec {
+            name,
+            lane_count,
+            width,
+            digest_bits,
+            default_rounds: default_rounds.unwrap_or(rounds.len()),
+            rounds,
+        })
+    }
+
+    /// Reads and parses a spec file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Spec, String> {
+        let text = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("{}: {}", path.as_ref().display(), e))?;
+        Spec::parse(&text)
+    }
+
+    /// The rotation amounts of each round, in order, as left rotations.
+    ///
+    /// Rotations inside `repeat` blocks are listed once per repetition.
+    pub fn rotations(&self) -> Vec<Vec<u32>> {
+        self.rounds
+            .iter()
+            .map(|ops| {
+                ops.iter()
+                    .filter_map(|op| match op {
+                        Op::Rotl(_, n) => Some(*n),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Replaces the rotation amounts, in the same layout as `rotations()`.
+    /// The amounts are taken modulo the lane width.
+    pub fn set_rotations(&mut self, rotations: &[Vec<u32>]) {
+        assert!(rotations.len() == self.rounds.len());
+
+        for (ops, round_rotations) in self.rounds.iter_mut().zip(rotations) {
+            let mut amounts = round_rotations.iter();
+            for op in ops.iter_mut() {
+                if let Op::Rotl(_, n) = op {
+                    *n = amounts.next().expect("too few rotations for the round") % self.width;
+                }
+            }
+            assert!(amounts.next().is_none(), "too many rotations for the round");
+        }
+    }
+
+    /// The input and output size in bytes, which is the full state.
+    pub fn size_bytes(&self) -> usize {
+        self.lane_count * self.width as usize / 8
+    }
+
+    pub fn digest_size_bytes(&self) -> usize {
+        self.digest_bits / 8
+    }
+
+    /// Runs the mixer, with the same interface as the mixers in
+    /// `lib::mixers`.
+    pub fn mix_input(&self, in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+        assert!(in_bytes.len() == self.size_bytes());
+        assert!(out_bytes.len() == self.size_bytes());
+
+        let lane_bytes = self.width as usize / 8;
+        let mask = u64::MAX >> (64 - self.width);
+
+        // Copy the state into the right layout.
+        let mut state: Vec<u64> = in_bytes
+            .chunks_exact(lane_bytes)
+            .map(|lane| {
+                let mut bytes = [0u8; 8];
+                bytes[..lane_bytes].copy_from_slice(lane);
+                u64::from_le_bytes(bytes)
+            })
+            .collect();
+
+        for ops in self.rounds.iter().cycle().take(rounds) {
+            for &op in ops {
+                let value = |state: &[u64], operand| match operand {
+                    Operand::Lane(i) => state[i],
+                    Operand::Const(n) => n,
+                };
+                match op {
+                    Op::Add(x, y) => state[x] = state[x].wrapping_add(value(&state, y)) & mask,
+                    Op::Xor(x, y) => state[x] = (state[x] ^ value(&state, y)) & mask,
+                    Op::Mul(x, y) => state[x] = state[x].wrapping_mul(value(&state, y)) & mask,
+                    Op::Rotl(x, n) => {
+                        let shift_right = (self.width - n) % self.width;
+                        state[x] = ((state[x] << n) | (state[x] >> shift_right)) & mask
+                    }
+                    Op::Swap(x, y) => state.swap(x, y),
+                }
+            }
+        }
+
+        // Copy the mixed state to the output.
+        for (lane, out) in state.iter().zip(out_bytes.chunks_exact_mut(lane_bytes)) {
+            out.copy_from_slice(&lane.to_le_bytes()[..lane_bytes]);
+        }
+    }
+}
+
+fn parse_number(s: &str) -> Result<u64, String> {
+    let parsed = match s.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(&hex.replace('_', ""), 16),
+        None => s.replace('_', "").parse::<u64>(),
+    };
+    parsed.map_err(|_| format!("`{}` is not a number", s))
+}
@@ -0,0 +1,208 @@
+//! Measures how well the accumulator mixers diffuse a difference in one block
+//! of a message as more blocks of random data are absorbed after it.
+//!
+//! For each accumulator this prints a table of bias and diffusion against the
+//! number of blocks absorbed after the one with the difference.
+
+use lib::{
+    mixers::{
+        aquahash, cityhash128, crc32c, fnv1a, highwayhash, meowhash, metrohash128, murmur3,
+        siphash, spookyhash2, wyhash, xxhash3, xxhash64,
+    },
+    stats::compute_chaining_stats,
+};
+
+/// Takes the input, the output, and the number of blocks to absorb.
+type AbsorbFunction = dyn Fn(&[u8], &mut [u8], usize);
+
+struct Accumulator<'a> {
+    name: &'a str,
+    absorb_function: &'a AbsorbFunction,
+    block_size: usize,  // In bytes.
+    state_size: usize,  // In bytes.
+    digest_size: usize, // In bytes.
+}
+
+const ACCUMULATORS: &[Accumulator] = &[
+    Accumulator {
+        name: "AquaHash accumulator",
+        absorb_function: &aquahash::mix_input,
+        block_size: aquahash::IN_SIZE_BYTES,
+        state_size: aquahash::OUT_SIZE_BYTES,
+        digest_size: aquahash::DIGEST_SIZE_BYTES,
+    },
+    Accumulator {
+        name: "CityHash128 accumulator",
+        absorb_function: &cityhash128::mix_input,
+        block_size: cityhash128::IN_SIZE_BYTES,
+        state_size: cityhash128::OUT_SIZE_BYTES,
+        digest_size: cityhash128::DIGEST_SIZE_BYTES,
+    },
+    Accumulator {
+        name: "CRC32C accumulator",
+        absorb_function: &crc32c::mix_input,
+        block_size: crc32c::IN_SIZE_BYTES,
+        state_size: crc32c::OUT_SIZE_BYTES,
+        digest_size: crc32c::DIGEST_SIZE_BYTES,
+    },
+    Accumulator {
+        name: "FNV1a (128-bit) accumulator",
+        absorb_function: &fnv1a::mix_input,
+        block_size: fnv1a::IN_SIZE_BYTES,
+        state_size: fnv1a::OUT_SIZE_BYTES,
+        digest_size: fnv1a::DIGEST_SIZE_BYTES,
+    },
+    Accumulator {
+        name: "HighwayHash update",
+        absorb_function: &highwayhash::mix_input,
+        block_size: highwayhash::IN_SIZE_BYTES,
+        state_size: highwayhash::OUT_SIZE_BYTES,
+        digest_size: highwayhash::DIGEST_SIZE_BYTES,
+    },
+    Accumulator {
+        name: "MeowHash v0.5 absorber",
+        absorb_function: &meowhash::mix_input,
+        block_size: meowhash::IN_SIZE_BYTES,
+        state_size: meowhash::OUT_SIZE_BYTES,
+        digest_size: meowhash::DIGEST_SIZE_BYTES,
+    },
+    Accumulator {
+        name: "MetroHash128 accumulator",
+        absorb_function: &metrohash128::mix_input,
+        block_size: metrohash128::IN_SIZE_BYTES,
+        state_size: metrohash128::OUT_SIZE_BYTES,
+        digest_size: metrohash128::DIGEST_SIZE_BYTES,
+    },
+    Accumulator {
+        name: "Murmur3 accumulator",
+        absorb_function: &murmur3::mix_input,
+        block_size: murmur3::IN_SIZE_BYTES,
+        state_size: murmur3::OUT_SIZE_BYTES,
+        digest_size: murmur3::DIGEST_SIZE_BYTES,
+    },
+    Accumulator {
+        name: "SipHash-1-3 absorber",
+        absorb_function: &siphash::absorb_input::<1>,
+        block_size: siphash::ABSORB_IN_SIZE_BYTES,
+        state_size: siphash::OUT_SIZE_BYTES,
+        digest_size: siphash::DIGEST_SIZE_BYTES,
+    },
+    Accumulator {
+        name: "SipHash-2-4 absorber",
+        absorb_function: &siphash::absorb_input::<2>,
+        block_size: siphash::ABSORB_IN_SIZE_BYTES,
+        state_size: siphash::OUT_SIZE_BYTES,
+        digest_size: siphash::DIGEST_SIZE_BYTES,
+    },
+    Accumulator {
+        name: "SpookyHash2 accumulator",
+        absorb_function: &spookyhash2::mix_input,
+        block_size: spookyhash2::IN_SIZE_BYTES,
+        state_size: spookyhash2::OUT_SIZE_BYTES,
+        digest_size: spookyhash2::DIGEST_SIZE_BYTES,
+    },
+    Accumulator {
+        name: "wyhash accumulator",
+        absorb_function: &wyhash::mix_input,
+        block_size: wyhash::IN_SIZE_BYTES,
+        state_size: wyhash::OUT_SIZE_BYTES,
+        digest_size: wyhash::DIGEST_SIZE_BYTES,
+    },
+    Accumulator {
+        name: "xxhash3 accumulator",
+        absorb_function: &xxhash3::mix_input,
+        block_size: xxhash3::IN_SIZE_BYTES,
+        state_size: xxhash3::OUT_SIZE_BYTES,
+        digest_size: xxhash3::DIGEST_SIZE_BYTES,
+    },
+    Accumulator {
+        name: "xxhash3 accumulator with scramble",
+        absorb_function: &xxhash3::mix_input_scrambled,
+        block_size: xxhash3::IN_SIZE_BYTES,
+        state_size: xxhash3::OUT_SIZE_BYTES,
+        digest_size: xxhash3::DIGEST_SIZE_BYTES,
+    },
+    Accumulator {
+        name: "xxhash64 accumulator",
+        absorb_function: &xxhash64::mix_input,
+        block_size: xxhash64::IN_SIZE_BYTES,
+        state_size: xxhash64::OUT_SIZE_BYTES,
+        digest_size: xxhash64::DIGEST_SIZE_BYTES,
+    },
+];
+
+fn main() {
+    let mut diff_block = 0;
+    let mut max_following_blocks = 4;
+    let mut samples = 1000;
+    let mut name_filters: Vec<String> = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("-") {
+            name_filters.push(arg.to_lowercase());
+            continue;
+        }
+
+        if arg == "--block" {
+            diff_block = args
+                .next()
+                .and_then(|n| n.parse::<usize>().ok())
+                .expect("--block expects the index of the block with the difference");
+            continue;
+        }
+
+        if arg == "--max-blocks" {
+            max_following_blocks = args
+                .next()
+                .and_then(|n| n.parse::<usize>().ok())
+                .expect("--max-blocks expects a block count");
+            continue;
+        }
+
+        if arg == "--samples" {
+            samples = args
+                .next()
+                .and_then(|n| n.parse::<usize>().ok())
+                .filter(|&n| n > 0)
+                .expect("--samples expects a sample count");
+            continue;
+        }
+
+        panic!("unknown argument `{}`", arg);
+    }
+
+    for accumulator in ACCUMULATORS.iter() {
+        let lower_name = accumulator.name.to_lowercase();
+        if !name_filters.is_empty() && !name_filters.iter().any(|f| lower_name.contains(f.as_str()))
+        {
+            continue;
+        }
+
+        println!("\n================================");
+        println!("{}, difference in block {}", accumulator.name, diff_block);
+        println!("    Blocks after   Avg bias   Max bias   Min diffusion");
+        for following_blocks in 0..=max_following_blocks {
+            let stats = compute_chaining_stats(
+                |message: &[u8], state: &mut [u8]| {
+                    let blocks = message.len() / accumulator.block_size;
+                    (accumulator.absorb_function)(message, state, blocks)
+                },
+                accumulator.block_size,
+                accumulator.state_size,
+                accumulator.digest_size,
+                diff_block,
+                following_blocks,
+                samples,
+            );
+
+            println!(
+                "    {:>12}   {:>8.3}   {:>8.3}   {:>8.1} bits",
+                following_blocks,
+                stats.average_bias(),
+                stats.max_bias(),
+                stats.min_input_bit_diffusion()
+            );
+        }
+    }
+}
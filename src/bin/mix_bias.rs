@@ -1,186 +1,498 @@
+use std::{
+    hash::{BuildHasherDefault, DefaultHasher},
+    path::Path,
+};
+
 #[allow(unused_imports)]
 use lib::{
+    arx::Spec,
+    corpus::{Corpus, Padding},
     mixers::{
-        aes, aquahash, cityhash128, fnv1a, goodhart, meowhash, metrohash128, murmur3, skein,
-        spookyhash2, tenthash, xxhash3,
-    },
-    stats::{
-        compute_stats, generate_bit_combinations, generate_counting, generate_gray_code,
-        generate_n_random_bits, generate_random, generate_single_1_bit,
+        aes, aquahash, ascon, blake2b, blake3, chacha, cityhash128, clmul, crc32c, finalizers,
+        fnv1a, goodhart,
+        hasher::{self, WriteStrategy},
+        highwayhash, keccak, meowhash, metrohash128, mum, murmur3, salsa20, siphash, skein,
+        spookyhash2, tenthash, wyhash, xxhash3, xxhash64,
     },
+    patterns::{self, Pattern, PATTERNS},
+    stats::{compute_derivative_bias, compute_stats, compute_stats_from_state},
 };
 
+/// Takes the input, the output, and the number of rounds.
+type MixFunction<'a> = dyn Fn(&[u8], &mut [u8], usize) + 'a;
+
+/// Takes a prior state, the input, the output, and the number of rounds.
+type MixFromStateFunction<'a> = dyn Fn(&[u8], &[u8], &mut [u8], usize) + 'a;
+
 struct Mixer<'a> {
     name: &'a str,
-    mix_function: &'a dyn Fn(&[u8], &mut [u8]),
+    mix_function: &'a MixFunction<'a>,
+
+    /// For mixers that can start from a prior state with the same layout as
+    /// their output, such as the accumulators.  These are also run from
+    /// random prior states.
+    mix_from_state: Option<&'a MixFromStateFunction<'a>>,
+
     input_size: usize,  // In bytes.
     output_size: usize, // In bytes.
     digest_size: usize, // In bytes.
+
+    /// The round counts to run the mixer with when none is given on the
+    /// command line.
+    rounds: &'a [usize],
 }
 
 const MIXERS: &[Mixer] = &[
     Mixer {
-        name: "AES, 1 round",
-        mix_function: &aes::mix_input_1_round,
-        input_size: aes::IN_SIZE_BYTES,
-        output_size: aes::OUT_SIZE_BYTES,
-        digest_size: aes::DIGEST_SIZE_BYTES,
-    },
-    Mixer {
-        name: "AES, 2 rounds",
-        mix_function: &aes::mix_input_2_rounds,
-        input_size: aes::IN_SIZE_BYTES,
-        output_size: aes::OUT_SIZE_BYTES,
-        digest_size: aes::DIGEST_SIZE_BYTES,
-    },
-    Mixer {
-        name: "AES, 3 rounds",
-        mix_function: &aes::mix_input_3_rounds,
+        name: "AES",
+        mix_function: &aes::mix_input,
+        mix_from_state: None,
         input_size: aes::IN_SIZE_BYTES,
         output_size: aes::OUT_SIZE_BYTES,
         digest_size: aes::DIGEST_SIZE_BYTES,
+        rounds: &[1, 2, aes::DEFAULT_ROUNDS],
     },
     Mixer {
         name: "AquaHash accumulator",
         mix_function: &aquahash::mix_input,
+        mix_from_state: None,
         input_size: aquahash::IN_SIZE_BYTES,
         output_size: aquahash::OUT_SIZE_BYTES,
         digest_size: aquahash::DIGEST_SIZE_BYTES,
+        rounds: &[aquahash::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Ascon permutation",
+        mix_function: &ascon::mix_input,
+        mix_from_state: None,
+        input_size: ascon::IN_SIZE_BYTES,
+        output_size: ascon::OUT_SIZE_BYTES,
+        digest_size: ascon::DIGEST_SIZE_BYTES,
+        rounds: &[1, 2, 3, 6, ascon::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "BLAKE2b compression",
+        mix_function: &blake2b::mix_input,
+        mix_from_state: None,
+        input_size: blake2b::IN_SIZE_BYTES,
+        output_size: blake2b::OUT_SIZE_BYTES,
+        digest_size: blake2b::DIGEST_SIZE_BYTES,
+        rounds: &[1, 2, blake2b::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "BLAKE3 compression",
+        mix_function: &blake3::mix_input,
+        mix_from_state: None,
+        input_size: blake3::IN_SIZE_BYTES,
+        output_size: blake3::OUT_SIZE_BYTES,
+        digest_size: blake3::DIGEST_SIZE_BYTES,
+        rounds: &[1, 2, blake3::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "ChaCha",
+        mix_function: &chacha::mix_input,
+        mix_from_state: None,
+        input_size: chacha::IN_SIZE_BYTES,
+        output_size: chacha::OUT_SIZE_BYTES,
+        digest_size: chacha::DIGEST_SIZE_BYTES,
+        rounds: &[1, 2, 4, chacha::DEFAULT_ROUNDS],
     },
     Mixer {
         name: "CityHash128 accumulator",
         mix_function: &cityhash128::mix_input,
+        mix_from_state: Some(&cityhash128::mix_input_from_state),
         input_size: cityhash128::IN_SIZE_BYTES,
         output_size: cityhash128::OUT_SIZE_BYTES,
         digest_size: cityhash128::DIGEST_SIZE_BYTES,
+        rounds: &[cityhash128::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Carry-less multiply",
+        mix_function: &clmul::mix_input,
+        mix_from_state: None,
+        input_size: clmul::IN_SIZE_BYTES,
+        output_size: clmul::OUT_SIZE_BYTES,
+        digest_size: clmul::DIGEST_SIZE_BYTES,
+        rounds: &[clmul::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "CRC32C accumulator",
+        mix_function: &crc32c::mix_input,
+        mix_from_state: None,
+        input_size: crc32c::IN_SIZE_BYTES,
+        output_size: crc32c::OUT_SIZE_BYTES,
+        digest_size: crc32c::DIGEST_SIZE_BYTES,
+        rounds: &[crc32c::DEFAULT_ROUNDS],
     },
     Mixer {
         name: "FNV1a (128-bit) accumulator",
         mix_function: &fnv1a::mix_input,
+        mix_from_state: None,
         input_size: fnv1a::IN_SIZE_BYTES,
         output_size: fnv1a::OUT_SIZE_BYTES,
         digest_size: fnv1a::DIGEST_SIZE_BYTES,
+        rounds: &[fnv1a::DEFAULT_ROUNDS],
     },
     Mixer {
-        name: "Goodhart mixer, 12 rounds",
+        name: "Goodhart mixer",
         mix_function: &goodhart::mix_input,
+        mix_from_state: None,
         input_size: goodhart::IN_SIZE_BYTES,
         output_size: goodhart::OUT_SIZE_BYTES,
         digest_size: goodhart::DIGEST_SIZE_BYTES,
+        rounds: &[goodhart::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Hasher: ahash (write)",
+        mix_function: &|i, o, r| {
+            hasher::mix_input::<BuildHasherDefault<ahash::AHasher>>(i, o, r, WriteStrategy::Write)
+        },
+        mix_from_state: None,
+        input_size: hasher::IN_SIZE_BYTES,
+        output_size: hasher::OUT_SIZE_BYTES,
+        digest_size: hasher::DIGEST_SIZE_BYTES,
+        rounds: &[hasher::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Hasher: DefaultHasher (write)",
+        mix_function: &|i, o, r| {
+            hasher::mix_input::<BuildHasherDefault<DefaultHasher>>(i, o, r, WriteStrategy::Write)
+        },
+        mix_from_state: None,
+        input_size: hasher::IN_SIZE_BYTES,
+        output_size: hasher::OUT_SIZE_BYTES,
+        digest_size: hasher::DIGEST_SIZE_BYTES,
+        rounds: &[hasher::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Hasher: foldhash (write)",
+        mix_function: &|i, o, r| {
+            hasher::mix_input::<foldhash::fast::FixedState>(i, o, r, WriteStrategy::Write)
+        },
+        mix_from_state: None,
+        input_size: hasher::IN_SIZE_BYTES,
+        output_size: hasher::OUT_SIZE_BYTES,
+        digest_size: hasher::DIGEST_SIZE_BYTES,
+        rounds: &[hasher::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Hasher: FxHasher (write_u64)",
+        mix_function: &|i, o, r| {
+            hasher::mix_input::<rustc_hash::FxBuildHasher>(i, o, r, WriteStrategy::WriteU64)
+        },
+        mix_from_state: None,
+        input_size: hasher::IN_SIZE_BYTES,
+        output_size: hasher::OUT_SIZE_BYTES,
+        digest_size: hasher::DIGEST_SIZE_BYTES,
+        rounds: &[hasher::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Hasher: FxHasher (write_u64, 4 seeds)",
+        mix_function: &|i, o, r| {
+            hasher::mix_input::<rustc_hash::FxBuildHasher>(i, o, r, WriteStrategy::WriteU64)
+        },
+        mix_from_state: None,
+        input_size: hasher::IN_SIZE_BYTES,
+        output_size: hasher::WIDE_OUT_SIZE_BYTES,
+        digest_size: hasher::DIGEST_SIZE_BYTES,
+        rounds: &[hasher::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "HighwayHash update",
+        mix_function: &highwayhash::mix_input,
+        mix_from_state: None,
+        input_size: highwayhash::IN_SIZE_BYTES,
+        output_size: highwayhash::OUT_SIZE_BYTES,
+        digest_size: highwayhash::DIGEST_SIZE_BYTES,
+        rounds: &[highwayhash::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Keccak-f[1600]",
+        mix_function: &keccak::mix_input,
+        mix_from_state: None,
+        input_size: keccak::IN_SIZE_BYTES,
+        output_size: keccak::OUT_SIZE_BYTES,
+        digest_size: keccak::DIGEST_SIZE_BYTES,
+        rounds: &[1, 2, 3, keccak::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Keccak-f[400]",
+        mix_function: &keccak::mix_input_400,
+        mix_from_state: None,
+        input_size: keccak::IN_SIZE_BYTES_400,
+        output_size: keccak::OUT_SIZE_BYTES_400,
+        digest_size: keccak::DIGEST_SIZE_BYTES_400,
+        rounds: &[1, 2, 3, keccak::DEFAULT_ROUNDS_400],
     },
     Mixer {
         name: "MeowHash v0.5 absorber",
         mix_function: &meowhash::mix_input,
+        mix_from_state: None,
         input_size: meowhash::IN_SIZE_BYTES,
         output_size: meowhash::OUT_SIZE_BYTES,
         digest_size: meowhash::DIGEST_SIZE_BYTES,
+        rounds: &[meowhash::DEFAULT_ROUNDS],
     },
     Mixer {
         name: "MetroHash128 accumulator",
         mix_function: &metrohash128::mix_input,
+        mix_from_state: Some(&metrohash128::mix_input_from_state),
         input_size: metrohash128::IN_SIZE_BYTES,
         output_size: metrohash128::OUT_SIZE_BYTES,
         digest_size: metrohash128::DIGEST_SIZE_BYTES,
+        rounds: &[metrohash128::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "mum folded multiply",
+        mix_function: &mum::mix_input,
+        mix_from_state: None,
+        input_size: mum::IN_SIZE_BYTES,
+        output_size: mum::OUT_SIZE_BYTES,
+        digest_size: mum::DIGEST_SIZE_BYTES,
+        rounds: &[mum::DEFAULT_ROUNDS],
     },
     Mixer {
         name: "Murmur3 accumulator",
         mix_function: &murmur3::mix_input,
+        mix_from_state: Some(&murmur3::mix_input_from_state),
         input_size: murmur3::IN_SIZE_BYTES,
         output_size: murmur3::OUT_SIZE_BYTES,
         digest_size: murmur3::DIGEST_SIZE_BYTES,
+        rounds: &[murmur3::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Salsa20",
+        mix_function: &salsa20::mix_input,
+        mix_from_state: None,
+        input_size: salsa20::IN_SIZE_BYTES,
+        output_size: salsa20::OUT_SIZE_BYTES,
+        digest_size: salsa20::DIGEST_SIZE_BYTES,
+        rounds: &[1, 2, 4, salsa20::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "SipHash-1-3 absorber",
+        mix_function: &siphash::absorb_input::<1>,
+        mix_from_state: None,
+        input_size: siphash::ABSORB_IN_SIZE_BYTES,
+        output_size: siphash::OUT_SIZE_BYTES,
+        digest_size: siphash::DIGEST_SIZE_BYTES,
+        rounds: &[siphash::DEFAULT_ABSORB_ROUNDS],
+    },
+    Mixer {
+        name: "SipHash-2-4 absorber",
+        mix_function: &siphash::absorb_input::<2>,
+        mix_from_state: None,
+        input_size: siphash::ABSORB_IN_SIZE_BYTES,
+        output_size: siphash::OUT_SIZE_BYTES,
+        digest_size: siphash::DIGEST_SIZE_BYTES,
+        rounds: &[siphash::DEFAULT_ABSORB_ROUNDS],
+    },
+    Mixer {
+        name: "SipHash finalizer",
+        mix_function: &siphash::mix_input,
+        mix_from_state: None,
+        input_size: siphash::IN_SIZE_BYTES,
+        output_size: siphash::OUT_SIZE_BYTES,
+        digest_size: siphash::DIGEST_SIZE_BYTES,
+        rounds: &[3, siphash::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Skein (Threefish-256)",
+        mix_function: &skein::mix_input_threefish,
+        mix_from_state: None,
+        input_size: skein::IN_SIZE_BYTES,
+        output_size: skein::OUT_SIZE_BYTES,
+        digest_size: skein::DIGEST_SIZE_BYTES,
+        rounds: &[tenthash::DEFAULT_ROUNDS, skein::THREEFISH_DEFAULT_ROUNDS],
     },
-    // Mixer {
-    //     name: "Skein, 7 rounds (not representative of actual Skein)",
-    //     mix_function: &skein::mix_input,
-    //     input_size: skein::IN_SIZE_BYTES,
-    //     output_size: skein::OUT_SIZE_BYTES,
-    //     digest_size: skein::DIGEST_SIZE_BYTES,
-    // },
     Mixer {
         name: "SpookyHash 2",
         mix_function: &spookyhash2::mix_input,
+        mix_from_state: Some(&spookyhash2::mix_input_from_state),
         input_size: spookyhash2::IN_SIZE_BYTES,
         output_size: spookyhash2::OUT_SIZE_BYTES,
         digest_size: spookyhash2::DIGEST_SIZE_BYTES,
+        rounds: &[spookyhash2::DEFAULT_ROUNDS],
     },
     Mixer {
         name: "TentHash",
         mix_function: &tenthash::mix_input,
+        mix_from_state: None,
         input_size: tenthash::IN_SIZE_BYTES,
         output_size: tenthash::OUT_SIZE_BYTES,
         digest_size: tenthash::DIGEST_SIZE_BYTES,
+        rounds: &[tenthash::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "wyhash accumulator",
+        mix_function: &wyhash::mix_input,
+        mix_from_state: None,
+        input_size: wyhash::IN_SIZE_BYTES,
+        output_size: wyhash::OUT_SIZE_BYTES,
+        digest_size: wyhash::DIGEST_SIZE_BYTES,
+        rounds: &[wyhash::DEFAULT_ROUNDS],
     },
     Mixer {
         name: "xxhash3 accumulator",
         mix_function: &xxhash3::mix_input,
+        mix_from_state: Some(&xxhash3::mix_input_from_state),
         input_size: xxhash3::IN_SIZE_BYTES,
         output_size: xxhash3::OUT_SIZE_BYTES,
         digest_size: xxhash3::DIGEST_SIZE_BYTES,
+        rounds: &[xxhash3::DEFAULT_ROUNDS],
     },
-];
-
-struct BitPattern<'a> {
-    name: &'a str,
-    gen_function: &'a dyn Fn(usize, &mut [u8]),
-
-    /// Number of rounds to run the pattern with. Zero is treated specially, and
-    /// means to use the bit width of the input.
-    rounds: usize,
-}
-
-const PATTERNS: &[BitPattern] = &[
-    BitPattern {
-        name: "random",
-        gen_function: &generate_random,
-        rounds: 1 << 16,
-    },
-    BitPattern {
-        name: "counting",
-        gen_function: &generate_counting,
-        rounds: 1 << 16,
-    },
-    // BitPattern {
-    //     name: "gray code",
-    //     gen_function: &generate_gray_code,
-    //     rounds: 1 << 16,
-    // },
-    BitPattern {
-        name: "bit combinations",
-        gen_function: &generate_bit_combinations,
-        rounds: 1 << 16,
-    },
-    BitPattern {
-        name: "4 random bits",
-        gen_function: &|seed, bytes| generate_n_random_bits(seed, bytes, 4),
-        rounds: 1 << 16,
-    },
-    BitPattern {
-        name: "8 random bits",
-        gen_function: &|seed, bytes| generate_n_random_bits(seed, bytes, 8),
-        rounds: 1 << 16,
-    },
-    BitPattern {
-        name: "single-bit",
-        gen_function: &generate_single_1_bit,
-
-        // NOTE: because this test has a small, fixed number of rounds by its
-        // nature, the generated statistics should be interpreted a little
-        // differently. In particular, even a very good mixing function is
-        // unlikely to achieve "perfect" avalanche or BIC by this measure,
-        // purely because it's impossible to collect enough samples to reduce
-        // variance enough.
-        rounds: 0,
+    Mixer {
+        name: "xxhash3 accumulator with scramble",
+        mix_function: &xxhash3::mix_input_scrambled,
+        mix_from_state: None,
+        input_size: xxhash3::IN_SIZE_BYTES,
+        output_size: xxhash3::OUT_SIZE_BYTES,
+        digest_size: xxhash3::DIGEST_SIZE_BYTES,
+        rounds: &[xxhash3::STRIPES_PER_BLOCK, xxhash3::STRIPES_PER_BLOCK + 1],
+    },
+    Mixer {
+        name: "xxhash64 accumulator",
+        mix_function: &xxhash64::mix_input,
+        mix_from_state: None,
+        input_size: xxhash64::IN_SIZE_BYTES,
+        output_size: xxhash64::OUT_SIZE_BYTES,
+        digest_size: xxhash64::DIGEST_SIZE_BYTES,
+        rounds: &[xxhash64::DEFAULT_ROUNDS],
+    },
+    // Finalizers.
+    Mixer {
+        name: "CityHash HashLen16 finalizer",
+        mix_function: &finalizers::mix_city_hash_len_16,
+        mix_from_state: None,
+        input_size: finalizers::HASH_LEN_16_IN_SIZE_BYTES,
+        output_size: finalizers::OUT_SIZE_BYTES,
+        digest_size: finalizers::DIGEST_SIZE_BYTES,
+        rounds: &[finalizers::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Moremur finalizer",
+        mix_function: &finalizers::mix_moremur,
+        mix_from_state: None,
+        input_size: finalizers::IN_SIZE_BYTES,
+        output_size: finalizers::OUT_SIZE_BYTES,
+        digest_size: finalizers::DIGEST_SIZE_BYTES,
+        rounds: &[finalizers::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Murmur3 fmix64 finalizer",
+        mix_function: &finalizers::mix_fmix64,
+        mix_from_state: None,
+        input_size: finalizers::IN_SIZE_BYTES,
+        output_size: finalizers::OUT_SIZE_BYTES,
+        digest_size: finalizers::DIGEST_SIZE_BYTES,
+        rounds: &[finalizers::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "SplitMix64 finalizer",
+        mix_function: &finalizers::mix_splitmix64,
+        mix_from_state: None,
+        input_size: finalizers::IN_SIZE_BYTES,
+        output_size: finalizers::OUT_SIZE_BYTES,
+        digest_size: finalizers::DIGEST_SIZE_BYTES,
+        rounds: &[finalizers::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Stafford Mix01 finalizer",
+        mix_function: &finalizers::mix_stafford_mix01,
+        mix_from_state: None,
+        input_size: finalizers::IN_SIZE_BYTES,
+        output_size: finalizers::OUT_SIZE_BYTES,
+        digest_size: finalizers::DIGEST_SIZE_BYTES,
+        rounds: &[finalizers::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Stafford Mix04 finalizer",
+        mix_function: &finalizers::mix_stafford_mix04,
+        mix_from_state: None,
+        input_size: finalizers::IN_SIZE_BYTES,
+        output_size: finalizers::OUT_SIZE_BYTES,
+        digest_size: finalizers::DIGEST_SIZE_BYTES,
+        rounds: &[finalizers::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Stafford Mix13 finalizer",
+        mix_function: &finalizers::mix_stafford_mix13,
+        mix_from_state: None,
+        input_size: finalizers::IN_SIZE_BYTES,
+        output_size: finalizers::OUT_SIZE_BYTES,
+        digest_size: finalizers::DIGEST_SIZE_BYTES,
+        rounds: &[finalizers::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "Stafford Mix14 finalizer",
+        mix_function: &finalizers::mix_stafford_mix14,
+        mix_from_state: None,
+        input_size: finalizers::IN_SIZE_BYTES,
+        output_size: finalizers::OUT_SIZE_BYTES,
+        digest_size: finalizers::DIGEST_SIZE_BYTES,
+        rounds: &[finalizers::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "xxh64 avalanche finalizer",
+        mix_function: &finalizers::mix_xxh64_avalanche,
+        mix_from_state: None,
+        input_size: finalizers::IN_SIZE_BYTES,
+        output_size: finalizers::OUT_SIZE_BYTES,
+        digest_size: finalizers::DIGEST_SIZE_BYTES,
+        rounds: &[finalizers::DEFAULT_ROUNDS],
+    },
+    Mixer {
+        name: "XXH3 avalanche finalizer",
+        mix_function: &finalizers::mix_xxh3_avalanche,
+        mix_from_state: None,
+        input_size: finalizers::IN_SIZE_BYTES,
+        output_size: finalizers::OUT_SIZE_BYTES,
+        digest_size: finalizers::DIGEST_SIZE_BYTES,
+        rounds: &[finalizers::DEFAULT_ROUNDS],
     },
 ];
 
 fn main() {
     let do_avalanche = true;
     let mut do_bic = false;
-    let mut name_filters = Vec::new();
+    let mut lane_bits = None;
+    let mut do_degree = false;
+    let mut rounds_override = None;
+    let mut samples_override = None;
+    let mut sweep_max_rounds = None;
+    let mut spec_paths = Vec::new();
+    let mut corpus_path = None;
+    let mut corpus_record_size = None;
+    let mut corpus_padding = Padding::Zero;
+    let mut corpus_offset = 0;
+
+    // Patterns given with `--pattern`, and their round counts if given.
+    let mut pattern_selection: Vec<(&Pattern, Option<usize>)> = Vec::new();
+    let mut thresholds = SweepThresholds {
+        max_bias: 0.05,
+        min_diffusion: 0.98,
+        min_bic_entropy: 0.99,
+    };
 
-    for arg in std::env::args().skip(1) {
+    // Each filter is a lowercase name fragment and, if given as
+    // `name@rounds`, a round count to run the matching mixers with.
+    let mut name_filters: Vec<(String, Option<usize>)> = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
         if !arg.starts_with("-") {
-            name_filters.push(arg.to_lowercase());
+            let filter = match arg.rsplit_once('@') {
+                Some((name, rounds)) => (
+                    name.to_lowercase(),
+                    Some(
+                        rounds
+                            .parse::<usize>()
+                            .expect("mixer selectors should be `name` or `name@rounds`"),
+                    ),
+                ),
+                None => (arg.to_lowercase(), None),
+            };
+            name_filters.push(filter);
             continue;
         }
 
@@ -193,44 +505,522 @@ fn main() {
             do_bic = true;
             continue;
         }
+
+        if arg == "--degree" {
+            do_degree = true;
+            continue;
+        }
+
+        if arg == "--lanes" {
+            lane_bits = Some(
+                args.next()
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .filter(|&n| n > 0)
+                    .expect("--lanes expects a lane width in bits"),
+            );
+            continue;
+        }
+
+        if arg == "--rounds" {
+            rounds_override = Some(
+                args.next()
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .expect("--rounds expects a round count"),
+            );
+            continue;
+        }
+
+        if arg == "--samples" {
+            samples_override = Some(
+                args.next()
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .filter(|&n| n > 0)
+                    .expect("--samples expects a sample count"),
+            );
+            continue;
+        }
+
+        if arg == "--pattern" {
+            let selector = args
+                .next()
+                .expect("--pattern expects a pattern name, or `name:rounds`");
+            let (name, rounds) = match selector.rsplit_once(':') {
+                Some((name, rounds)) => (
+                    name,
+                    Some(
+                        rounds
+                            .parse::<usize>()
+                            .expect("pattern selectors should be `name` or `name:rounds`"),
+                    ),
+                ),
+                None => (&selector[..], None),
+            };
+            let pattern = patterns::find(name)
+                .unwrap_or_else(|| panic!("unknown pattern `{}`, see --list-patterns", name));
+            pattern_selection.push((pattern, rounds));
+            continue;
+        }
+
+        if arg == "--list-patterns" {
+            println!("Patterns marked with * are run when none are chosen.\n");
+            for pattern in PATTERNS.iter() {
+                let rounds = if pattern.rounds == 0 {
+                    "input bits".to_string()
+                } else {
+                    pattern.rounds.to_string()
+                };
+                println!(
+                    "{:<20} {:>10}  {}  {}",
+                    pattern.name,
+                    rounds,
+                    if pattern.is_default { "*" } else { " " },
+                    pattern.description
+                );
+            }
+            return;
+        }
+
+        if arg == "--corpus" {
+            corpus_path = Some(args.next().expect("--corpus expects a corpus file path"));
+            continue;
+        }
+
+        if arg == "--corpus-record-size" {
+            corpus_record_size = Some(
+                args.next()
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .filter(|&n| n > 0)
+                    .expect("--corpus-record-size expects a record size in bytes"),
+            );
+            continue;
+        }
+
+        if arg == "--corpus-offset" {
+            corpus_offset = args
+                .next()
+                .and_then(|n| n.parse::<usize>().ok())
+                .expect("--corpus-offset expects a byte offset");
+            continue;
+        }
+
+        if arg == "--corpus-padding" {
+            corpus_padding = args
+                .next()
+                .map(|name| Padding::parse(&name).unwrap_or_else(|e| panic!("{}", e)))
+                .expect("--corpus-padding expects `zero`, `repeat`, or `length`");
+            continue;
+        }
+
+        if arg == "--spec" {
+            spec_paths.push(args.next().expect("--spec expects a spec file path"));
+            continue;
+        }
+
+        if arg == "--sweep" {
+            sweep_max_rounds = Some(
+                args.next()
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .filter(|&n| n > 0)
+                    .expect("--sweep expects a maximum round count"),
+            );
+            continue;
+        }
+
+        if arg == "--bias-threshold" {
+            thresholds.max_bias = args
+                .next()
+                .and_then(|n| n.parse::<f64>().ok())
+                .expect("--bias-threshold expects a bias in [0, 1]");
+            continue;
+        }
+
+        if arg == "--diffusion-threshold" {
+            thresholds.min_diffusion = args
+                .next()
+                .and_then(|n| n.parse::<f64>().ok())
+                .expect("--diffusion-threshold expects a fraction of the output bits");
+            continue;
+        }
+
+        if arg == "--bic-threshold" {
+            thresholds.min_bic_entropy = args
+                .next()
+                .and_then(|n| n.parse::<f64>().ok())
+                .expect("--bic-threshold expects an entropy in [0, 1]");
+            continue;
+        }
+    }
+
+    // A corpus of keys, as a text file of lines or, with a record size, a
+    // binary file.
+    let corpus = corpus_path.as_ref().map(|path| {
+        let mut corpus = match corpus_record_size {
+            Some(size) => Corpus::load_chunks(path, size, corpus_padding),
+            None => Corpus::load_lines(path, corpus_padding),
+        }
+        .unwrap_or_else(|e| panic!("invalid corpus: {}", e));
+        corpus.offset = corpus_offset;
+        corpus
+    });
+    if let Some(corpus) = &corpus {
+        assert!(!corpus.is_empty(), "the corpus has no records");
+    }
+    let corpus_name = corpus_path.as_ref().map(|path| {
+        let file_name = Path::new(path).file_name().unwrap_or_default();
+        format!("corpus {}", file_name.to_string_lossy())
+    });
+    let corpus_function = corpus
+        .as_ref()
+        .map(|corpus| move |index, bytes: &mut [u8]| corpus.generate(index, bytes));
+
+    // The patterns to run, with their round counts resolved.  A round count
+    // given with `--pattern` takes precedence over `--samples`.  Like specs,
+    // when a corpus is given the built-in patterns only run if chosen.
+    if pattern_selection.is_empty() && corpus.is_none() {
+        pattern_selection = PATTERNS
+            .iter()
+            .filter(|pattern| pattern.is_default)
+            .map(|pattern| (pattern, None))
+            .collect();
     }
+    let mut patterns: Vec<Pattern> = pattern_selection
+        .iter()
+        .map(|&(pattern, rounds)| Pattern {
+            rounds: rounds.or(samples_override).unwrap_or(pattern.rounds),
+            ..*pattern
+        })
+        .collect();
+    if let (Some(corpus), Some(name), Some(gen_function)) =
+        (&corpus, &corpus_name, &corpus_function)
+    {
+        // Each record once, by default.
+        patterns.push(Pattern {
+            name,
+            gen_function,
+            rounds: samples_override.unwrap_or(corpus.len()),
+            description: "records from a corpus file",
+            is_default: false,
+        });
+    }
+
+    // Mixers from spec files, named by the spec or else by the file name.
+    let specs: Vec<(String, Spec)> = spec_paths
+        .iter()
+        .map(|path| {
+            let spec = Spec::from_file(path).unwrap_or_else(|e| panic!("invalid spec: {}", e));
+            let name = spec.name.clone().unwrap_or_else(|| path.clone());
+            (name, spec)
+        })
+        .collect();
+    let spec_functions: Vec<_> = specs
+        .iter()
+        .map(|(_, spec)| {
+            move |input: &[u8], output: &mut [u8], rounds| spec.mix_input(input, output, rounds)
+        })
+        .collect();
+    let spec_mixers: Vec<Mixer> = specs
+        .iter()
+        .zip(spec_functions.iter())
+        .map(|((name, spec), mix_function)| Mixer {
+            name,
+            mix_function,
+            mix_from_state: None,
+            input_size: spec.size_bytes(),
+            output_size: spec.size_bytes(),
+            digest_size: spec.digest_size_bytes(),
+            rounds: std::slice::from_ref(&spec.default_rounds),
+        })
+        .collect();
 
-    for mixer in MIXERS.iter() {
-        if !name_filters.is_empty() {
+    // A sweep runs its own range of round counts, so it can't be combined
+    // with choosing them.
+    if sweep_max_rounds.is_some()
+        && (rounds_override.is_some() || name_filters.iter().any(|(_, r)| r.is_some()))
+    {
+        panic!("--sweep can't be combined with --rounds or `name@rounds`");
+    }
+
+    for mixer in MIXERS.iter().chain(spec_mixers.iter()) {
+        let default_rounds = match rounds_override {
+            Some(rounds) => vec![rounds],
+            None => mixer.rounds.to_vec(),
+        };
+
+        // Spec mixers always run, and when any are given the built-in mixers
+        // only run if named.
+        let is_spec = spec_mixers.iter().any(|m| std::ptr::eq(m, mixer));
+
+        let mut rounds_list = Vec::new();
+        if name_filters.is_empty() {
+            if specs.is_empty() {
+                rounds_list = default_rounds.clone();
+            }
+        } else {
             let lower_name = mixer.name.to_lowercase();
+            for (filter, rounds) in name_filters.iter() {
+                if !lower_name.contains(filter.as_str()) {
+                    continue;
+                }
+                let rounds = match rounds {
+                    Some(rounds) => std::slice::from_ref(rounds),
+                    None => &default_rounds[..],
+                };
+                for &r in rounds {
+                    if !rounds_list.contains(&r) {
+                        rounds_list.push(r);
+                    }
+                }
+            }
+        }
 
-            if !name_filters
-                .iter()
-                .any(|filter| lower_name.contains(filter))
-            {
-                continue;
+        if rounds_list.is_empty() && is_spec {
+            rounds_list = default_rounds;
+        }
+
+        if rounds_list.is_empty() {
+            continue;
+        }
+
+        if let Some(max_rounds) = sweep_max_rounds {
+            println!("\n================================");
+            println!("{}, 1-{} rounds", mixer.name, max_rounds);
+            for pattern in patterns.iter() {
+                println!("\nInput bit pattern: {}", pattern.name);
+                sweep_rounds(
+                    mixer,
+                    pattern,
+                    max_rounds,
+                    pattern.rounds_for(mixer.input_size),
+                    &thresholds,
+                    do_bic,
+                    false,
+                );
+
+                if mixer.mix_from_state.is_some() {
+                    println!("\nInput bit pattern: {}, random prior state", pattern.name);
+                    sweep_rounds(
+                        mixer,
+                        pattern,
+                        max_rounds,
+                        pattern.rounds_for(mixer.input_size),
+                        &thresholds,
+                        do_bic,
+                        true,
+                    );
+                }
             }
+            continue;
         }
 
-        println!("\n================================");
-        println!("{}", mixer.name);
-        for pattern in PATTERNS.iter() {
-            println!("\nInput bit pattern: {}", pattern.name);
-            let stats = compute_stats(
+        for &rounds in rounds_list.iter() {
+            let name = format!(
+                "{}, {} round{}",
+                mixer.name,
+                rounds,
+                if rounds == 1 { "" } else { "s" }
+            );
+            let mix = |input: &[u8], output: &mut [u8]| (mixer.mix_function)(input, output, rounds);
+
+            println!("\n================================");
+            println!("{}", name);
+            if do_degree {
+                print_degree_report(
+                    &mix,
+                    mixer.input_size,
+                    mixer.output_size,
+                    samples_override.unwrap_or(1 << 14),
+                );
+            }
+            for pattern in patterns.iter() {
+                let sample_count = pattern.rounds_for(mixer.input_size);
+
+                println!("\nInput bit pattern: {}", pattern.name);
+                let stats = compute_stats(
+                    pattern.gen_function,
+                    mix,
+                    mixer.input_size,
+                    mixer.output_size,
+                    mixer.digest_size,
+                    sample_count,
+                    do_avalanche,
+                    do_bic,
+                );
+                stats.print_report();
+                if let Some(lane_bits) = lane_bits {
+                    stats.print_lane_report(lane_bits);
+                }
+                if do_avalanche {
+                    stats
+                        .write_avalanche_png(format!("{} - {}.png", name, pattern.name))
+                        .unwrap();
+                }
+
+                if let Some(mix_from_state) = mixer.mix_from_state {
+                    println!("\nInput bit pattern: {}, random prior state", pattern.name);
+                    let stats = compute_stats_from_state(
+                        pattern.gen_function,
+                        |state: &[u8], input: &[u8], output: &mut [u8]| {
+                            mix_from_state(state, input, output, rounds)
+                        },
+                        mixer.input_size,
+                        mixer.output_size,
+                        mixer.output_size,
+                        mixer.digest_size,
+                        sample_count,
+                        do_avalanche,
+                        do_bic,
+                    );
+                    stats.print_report();
+                    if let Some(lane_bits) = lane_bits {
+                        stats.print_lane_report(lane_bits);
+                    }
+                    if do_avalanche {
+                        stats
+                            .write_avalanche_png(format!(
+                                "{} - {} - random prior state.png",
+                                name, pattern.name
+                            ))
+                            .unwrap();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Prints the bias of the 2nd and 3rd-order derivatives of `mix` over random
+/// inputs.  See `compute_derivative_bias()`.
+fn print_degree_report<F>(mix: &F, input_size: usize, output_size: usize, samples: usize)
+where
+    F: Fn(&[u8], &mut [u8]),
+{
+    println!("\nAlgebraic degree, random inputs:");
+    for (order, meaning) in [(2, "affine"), (3, "at most quadratic")] {
+        let biases = compute_derivative_bias(mix, input_size, output_size, order, samples);
+        let min = biases.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = biases.iter().copied().fold(0.0, f64::max);
+        let avg = biases.iter().sum::<f64>() / biases.len() as f64;
+        println!(
+            "    Order-{} derivative bias (1.00 = {}):
+        Min: {:0.2}
+        Avg: {:0.2}
+        Max: {:0.2}",
+            order, meaning, min, avg, max,
+        );
+    }
+}
+
+/// The levels at which a mixer is considered to have reached full diffusion
+/// in a round sweep.
+struct SweepThresholds {
+    /// The max bias must be at or below this.
+    max_bias: f64,
+
+    /// The min input bit diffusion must be at or above this fraction of the
+    /// digest bits.  The digest, rather than the full output, since wide
+    /// accumulator states only need to diffuse into as many bits as the hash
+    /// eventually outputs.
+    min_diffusion: f64,
+
+    /// The min BIC entropy must be at or above this.
+    min_bic_entropy: f64,
+}
+
+/// Runs `mixer` at 1 through `max_rounds` rounds, printing a table of the
+/// key metrics at each round count and the first round count at which each
+/// metric reaches its threshold.
+///
+/// With `random_prior_state`, the mixer is run from random prior states
+/// with its `mix_from_state` function instead.
+fn sweep_rounds(
+    mixer: &Mixer,
+    pattern: &Pattern,
+    max_rounds: usize,
+    sample_count: usize,
+    thresholds: &SweepThresholds,
+    do_bic: bool,
+    random_prior_state: bool,
+) {
+    let digest_bits = mixer.digest_size.min(mixer.output_size) * 8;
+    let diffusion_threshold = thresholds.min_diffusion * digest_bits as f64;
+
+    let mut first_bias = None;
+    let mut first_diffusion = None;
+    let mut first_bic = None;
+
+    println!("    Rounds   Max bias   Min diffusion   Min BIC entropy");
+    for rounds in 1..=max_rounds {
+        let stats = match mixer.mix_from_state {
+            Some(mix_from_state) if random_prior_state => compute_stats_from_state(
                 pattern.gen_function,
-                mixer.mix_function,
+                |state: &[u8], input: &[u8], output: &mut [u8]| {
+                    mix_from_state(state, input, output, rounds)
+                },
                 mixer.input_size,
                 mixer.output_size,
+                mixer.output_size,
                 mixer.digest_size,
-                if pattern.rounds == 0 {
-                    mixer.input_size * 8
-                } else {
-                    pattern.rounds
-                },
-                do_avalanche,
+                sample_count,
+                true,
                 do_bic,
-            );
-            stats.print_report();
-            if do_avalanche {
-                stats
-                    .write_avalanche_png(&format!("{} - {}.png", mixer.name, pattern.name))
-                    .unwrap();
-            }
+            ),
+            _ => compute_stats(
+                pattern.gen_function,
+                |input: &[u8], output: &mut [u8]| (mixer.mix_function)(input, output, rounds),
+                mixer.input_size,
+                mixer.output_size,
+                mixer.digest_size,
+                sample_count,
+                true,
+                do_bic,
+            ),
+        };
+
+        let max_bias = stats.max_bias();
+        let min_diffusion = stats.min_input_bit_diffusion();
+        if first_bias.is_none() && max_bias <= thresholds.max_bias {
+            first_bias = Some(rounds);
         }
+        if first_diffusion.is_none() && min_diffusion >= diffusion_threshold {
+            first_diffusion = Some(rounds);
+        }
+
+        let bic_column = if do_bic {
+            let min_bic_entropy = stats.min_bic_entropy();
+            if first_bic.is_none() && min_bic_entropy >= thresholds.min_bic_entropy {
+                first_bic = Some(rounds);
+            }
+            format!("{:0.4}", min_bic_entropy)
+        } else {
+            "-".into()
+        };
+
+        println!(
+            "    {:>6}   {:>8.2}   {:>8.1} bits   {:>15}",
+            rounds, max_bias, min_diffusion, bic_column
+        );
+    }
+
+    let describe = |first: Option<usize>| match first {
+        Some(rounds) => format!("{}", rounds),
+        None => format!("not reached within {} rounds", max_rounds),
+    };
+    println!("    First round count reaching:");
+    println!(
+        "        Max bias <= {:0.2}: {}",
+        thresholds.max_bias,
+        describe(first_bias)
+    );
+    println!(
+        "        Min diffusion >= {:0.1} bits: {}",
+        diffusion_threshold,
+        describe(first_diffusion)
+    );
+    if do_bic {
+        println!(
+            "        Min BIC entropy >= {:0.4}: {}",
+            thresholds.min_bic_entropy,
+            describe(first_bic)
+        );
     }
 }
@@ -0,0 +1,243 @@
+//! Searches for good rotation constants for an ARX mixer.
+//!
+//! The mixer's structure is given as an ARX spec file (see `lib::arx`), and
+//! every rotation in it is a free parameter.  Each restart starts from random
+//! rotations and improves them by simulated annealing, scoring candidates
+//! with `compute_stats` on a small sample budget.  The best schedule from each
+//! restart is a finalist, and the top finalists are rescored with a full
+//! sample budget and printed.
+
+use lib::{
+    arx::Spec,
+    stats::{compute_stats, generate_n_random_bits, generate_random},
+};
+use nanorand::{Rng, WyRand};
+
+struct Options {
+    spec_path: String,
+    rounds: Option<usize>,
+    restarts: usize,
+    steps: usize,
+    temperature: f64,
+    samples: usize,
+    final_samples: usize,
+    finalists: usize,
+    seed: u64,
+}
+
+/// How well a schedule mixes.  Lower is better.
+#[derive(Debug, Copy, Clone)]
+struct Score {
+    /// The worst max bias over the input patterns.
+    max_bias: f64,
+    /// The worst average bias over the input patterns.
+    avg_bias: f64,
+    /// The worst min input bit diffusion over the input patterns, in bits.
+    min_diffusion: f64,
+}
+
+impl Score {
+    /// The single number that's optimized.  The average bias breaks ties
+    /// between schedules with the same max bias, which otherwise happens a
+    /// lot with small sample budgets.
+    fn value(&self) -> f64 {
+        self.max_bias + self.avg_bias
+    }
+}
+
+fn main() {
+    let mut options = Options {
+        spec_path: String::new(),
+        rounds: None,
+        restarts: 8,
+        steps: 200,
+        temperature: 0.05,
+        samples: 128,
+        final_samples: 1000,
+        finalists: 5,
+        seed: 0x9c3b1e8f27d4a650,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        // A count, which must be at least `min`.
+        let mut count = |what: &str, min: usize| -> usize {
+            args.next()
+                .and_then(|n| n.parse::<usize>().ok())
+                .filter(|&n| n >= min)
+                .unwrap_or_else(|| panic!("{} expects {}", arg, what))
+        };
+        match arg.as_str() {
+            "--spec" => {
+                options.spec_path = args.next().expect("--spec expects a spec file path");
+            }
+            "--rounds" => options.rounds = Some(count("a round count", 1)),
+            "--restarts" => options.restarts = count("a restart count", 1),
+            "--steps" => options.steps = count("a step count", 0),
+            "--temperature" => {
+                options.temperature = args
+                    .next()
+                    .and_then(|t| t.parse::<f64>().ok())
+                    .filter(|t| t.is_finite() && *t >= 0.0)
+                    .expect("--temperature expects a non-negative starting temperature");
+            }
+            "--samples" => options.samples = count("a sample count", 1),
+            "--final-samples" => options.final_samples = count("a sample count", 1),
+            "--finalists" => options.finalists = count("a finalist count", 1),
+            "--seed" => {
+                let seed = args.next().expect("--seed expects a seed");
+                options.seed = match seed.strip_prefix("0x") {
+                    Some(hex) => u64::from_str_radix(hex, 16),
+                    None => seed.parse::<u64>(),
+                }
+                .unwrap_or_else(|_| {
+                    panic!(
+                        "--seed expects a decimal or 0x-prefixed hex u64, got `{}`",
+                        seed
+                    )
+                });
+            }
+            _ => panic!("unknown argument `{}`", arg),
+        }
+    }
+    assert!(!options.spec_path.is_empty(), "--spec is required");
+
+    let mut spec =
+        Spec::from_file(&options.spec_path).unwrap_or_else(|e| panic!("invalid spec: {}", e));
+    let rounds = options.rounds.unwrap_or(spec.default_rounds);
+    assert!(rounds > 0, "the round count must be at least 1");
+    let layout: Vec<usize> = spec.rotations().iter().map(|r| r.len()).collect();
+    let original: Vec<u32> = spec.rotations().concat();
+
+    // Only the rotations of rounds that are actually run are searched, and
+    // the rest keep their values from the spec.
+    let parameter_count: usize = layout[..rounds.min(layout.len())].iter().sum();
+    assert!(parameter_count > 0, "the spec has no rotations to search");
+
+    println!(
+        "Searching {} rotations of \"{}\" at {} rounds.",
+        parameter_count,
+        spec.name.as_deref().unwrap_or(&options.spec_path),
+        rounds
+    );
+
+    let mut rng = WyRand::new_seed(options.seed);
+    let width = spec.width;
+    let random_rotation = |rng: &mut WyRand| rng.generate_range(1..width);
+
+    let mut finalists: Vec<(Vec<u32>, Score)> = Vec::new();
+    for restart in 0..options.restarts {
+        let mut current = original.clone();
+        for rotation in current[..parameter_count].iter_mut() {
+            *rotation = random_rotation(&mut rng);
+        }
+        let mut current_score = score(&mut spec, &layout, &current, rounds, options.samples);
+        let mut best = (current.clone(), current_score);
+
+        for step in 0..options.steps {
+            // Cool linearly to zero, which makes the last steps pure
+            // hill-climbing.
+            let temperature = options.temperature * (1.0 - step as f64 / options.steps as f64);
+
+            let mut candidate = current.clone();
+            let i = rng.generate_range(0..parameter_count);
+            candidate[i] = random_rotation(&mut rng);
+            let candidate_score = score(&mut spec, &layout, &candidate, rounds, options.samples);
+
+            let delta = candidate_score.value() - current_score.value();
+            let accept = delta <= 0.0
+                || (temperature > 0.0 && rng.generate::<f64>() < (-delta / temperature).exp());
+            if accept {
+                current = candidate;
+                current_score = candidate_score;
+                if current_score.value() < best.1.value() {
+                    best = (current.clone(), current_score);
+                }
+            }
+        }
+
+        println!(
+            "Restart {}/{}: max bias {:0.3}, avg bias {:0.3}",
+            restart + 1,
+            options.restarts,
+            best.1.max_bias,
+            best.1.avg_bias
+        );
+        finalists.push(best);
+    }
+
+    // Rescore the best finalists with the full budget.
+    finalists.sort_by(|a, b| a.1.value().total_cmp(&b.1.value()));
+    finalists.truncate(options.finalists);
+    for finalist in finalists.iter_mut() {
+        finalist.1 = score(
+            &mut spec,
+            &layout,
+            &finalist.0,
+            rounds,
+            options.final_samples,
+        );
+    }
+    finalists.sort_by(|a, b| a.1.value().total_cmp(&b.1.value()));
+
+    println!("\nBest schedules ({} samples):", options.final_samples);
+    for (rotations, score) in finalists.iter() {
+        println!(
+            "\n    Max bias: {:0.3}  Avg bias: {:0.3}  Min diffusion: {:0.1} bits",
+            score.max_bias, score.avg_bias, score.min_diffusion
+        );
+        println!("    Rotations: {:?}", group(&layout, rotations));
+    }
+}
+
+/// Splits flat rotations into per-round lists.
+fn group(layout: &[usize], rotations: &[u32]) -> Vec<Vec<u32>> {
+    let mut rest = rotations;
+    layout
+        .iter()
+        .map(|&n| {
+            let (round, tail) = rest.split_at(n);
+            rest = tail;
+            round.to_vec()
+        })
+        .collect()
+}
+
+/// Scores the spec with the given rotations, over a random input pattern and
+/// a sparse one.
+fn score(
+    spec: &mut Spec,
+    layout: &[usize],
+    rotations: &[u32],
+    rounds: usize,
+    samples: usize,
+) -> Score {
+    spec.set_rotations(&group(layout, rotations));
+    let spec = &*spec;
+
+    let patterns: [fn(usize, &mut [u8]); 2] = [generate_random, |seed, bytes| {
+        generate_n_random_bits(seed, bytes, 4)
+    }];
+
+    let mut score = Score {
+        max_bias: 0.0,
+        avg_bias: 0.0,
+        min_diffusion: f64::INFINITY,
+    };
+    for pattern in patterns {
+        let stats = compute_stats(
+            pattern,
+            |input: &[u8], output: &mut [u8]| spec.mix_input(input, output, rounds),
+            spec.size_bytes(),
+            spec.size_bytes(),
+            spec.digest_size_bytes(),
+            samples,
+            true,
+            false,
+        );
+        score.max_bias = score.max_bias.max(stats.max_bias());
+        score.avg_bias = score.avg_bias.max(stats.average_bias());
+        score.min_diffusion = score.min_diffusion.min(stats.min_input_bit_diffusion());
+    }
+    score
+}
@@ -0,0 +1,144 @@
+//! Input generation from a corpus of real keys, so that mixers can be tested
+//! on inputs shaped like the ones they'll actually see.
+//!
+//! A corpus is either a text file with one record per line, or a binary file
+//! split into fixed-size records.  Each round index picks a record, wrapping
+//! around the corpus, and places it in the input block according to a
+//! `Padding` policy, starting at the corpus's offset.
+
+use std::path::Path;
+
+/// How a record is placed into an input block.  Records longer than the
+/// block are truncated in every case.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Padding {
+    /// The record, followed by zeros.
+    Zero,
+    /// The record repeated to fill the block.  Empty records give zeros.
+    Repeat,
+    /// The record's length as a little-endian u32, then the record, then
+    /// zeros.
+    LengthPrefixed,
+}
+
+impl Padding {
+    /// Parses `zero`, `repeat`, or `length`.
+    pub fn parse(name: &str) -> Result<Padding, String> {
+        match name {
+            "zero" => Ok(Padding::Zero),
+            "repeat" => Ok(Padding::Repeat),
+            "length" => Ok(Padding::LengthPrefixed),
+            _ => Err(format!(
+                "unknown padding `{}`, expected `zero`, `repeat`, or `length`",
+                name
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Corpus {
+    records: Vec<Vec<u8>>,
+    pub padding: Padding,
+
+    /// The byte offset in the input block that records are placed at, for
+    /// keys that sit partway into a block (e.g. after a prefix or inside a
+    /// struct).  The bytes before it are zeros.  Zero by default.
+    pub offset: usize,
+}
+
+impl Corpus {
+    /// A corpus with one record per line.  Line endings (`\n` or `\r\n`) are
+    /// not part of the records, and the lines don't need to be valid UTF-8.
+    pub fn from_lines(data: &[u8], padding: Padding) -> Corpus {
+        let data = data.strip_suffix(b"\n").unwrap_or(data);
+        let records = if data.is_empty() {
+            Vec::new()
+        } else {
+            data.split(|&b| b == b'\n')
+                .map(|line| line.strip_suffix(b"\r").unwrap_or(line).to_vec())
+                .collect()
+        };
+        Corpus {
+            records,
+            padding,
+            offset: 0,
+        }
+    }
+
+    /// A corpus of `record_size`-byte records.  A shorter record at the end
+    /// is kept as is.
+    pub fn from_chunks(data: &[u8], record_size: usize, padding: Padding) -> Corpus {
+        assert!(record_size > 0);
+        Corpus {
+            records: data.chunks(record_size).map(|r| r.to_vec()).collect(),
+            padding,
+            offset: 0,
+        }
+    }
+
+    /// Reads a corpus file with one record per line.
+    pub fn load_lines<P: AsRef<Path>>(path: P, padding: Padding) -> Result<Corpus, String> {
+        Ok(Corpus::from_lines(&read(path.as_ref())?, padding))
+    }
+
+    /// Reads a binary corpus file, split into `record_size`-byte records.
+    pub fn load_chunks<P: AsRef<Path>>(
+        path: P,
+        record_size: usize,
+        padding: Padding,
+    ) -> Result<Corpus, String> {
+        Ok(Corpus::from_chunks(
+            &read(path.as_ref())?,
+            record_size,
+            padding,
+        ))
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn record(&self, index: usize) -> &[u8] {
+        &self.records[index]
+    }
+
+    /// Fills `bytes` with record `index`, wrapping around the corpus, placed
+    /// at the offset according to the padding policy.  Everything from the
+    /// offset on is filled as if it were the whole block.
+    pub fn generate(&self, index: usize, bytes: &mut [u8]) {
+        assert!(!self.is_empty());
+        let record = &self.records[index % self.records.len()];
+
+        bytes.fill(0);
+        let start = self.offset.min(bytes.len());
+        let bytes = &mut bytes[start..];
+        match self.padding {
+            Padding::Zero => {
+                let n = record.len().min(bytes.len());
+                bytes[..n].copy_from_slice(&record[..n]);
+            }
+            Padding::Repeat => {
+                if !record.is_empty() {
+                    for (byte, &r) in bytes.iter_mut().zip(record.iter().cycle()) {
+                        *byte = r;
+                    }
+                }
+            }
+            Padding::LengthPrefixed => {
+                let prefix = (record.len() as u32).to_le_bytes();
+                for (byte, &r) in bytes.iter_mut().zip(prefix.iter().chain(record.iter())) {
+                    *byte = r;
+                }
+            }
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))
+}
@@ -1,2 +1,6 @@
+pub mod aes_round;
+pub mod arx;
+pub mod corpus;
 pub mod mixers;
+pub mod patterns;
 pub mod stats;
@@ -1,13 +1,13 @@
-#[cfg(target_arch = "x86")]
-use core::arch::x86::*;
-#[cfg(target_arch = "x86_64")]
-use core::arch::x86_64::*;
+use crate::aes_round::aesenc;
 
 pub const IN_SIZE_BYTES: usize = 128 / 8;
 pub const OUT_SIZE_BYTES: usize = 128 / 8;
 pub const DIGEST_SIZE_BYTES: usize = 128 / 8;
 
-// Some random numbers, for playing with the number of rounds below.
+/// The minimum number of rounds for full 128-bit diffusion.
+pub const DEFAULT_ROUNDS: usize = 3;
+
+// Some random numbers to use as round keys.  They repeat after 8 rounds.
 const KEYS: &[u128] = &[
     0x5aee66ffbc9d5f254dd19917b03fb552,
     0xb625245574d76546f7007e2431b3c833,
@@ -29,32 +29,16 @@ pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
     assert!(in_bytes.len() == IN_SIZE_BYTES);
     assert!(out_bytes.len() == OUT_SIZE_BYTES);
 
-    let mut state: __m128i = unsafe { _mm_loadu_si128(in_bytes.as_ptr().cast()) };
-    unsafe {
-        for i in 0..rounds {
-            state = _mm_aesenc_si128(state, std::mem::transmute(KEYS[i]));
-        }
-
-        // Note: `_mm_aesenclast_si128()` doesn't do as much mixing as
-        // `_mm_aesenc_si128()`.  Therfore uncommenting the line below dosen't
-        // fully diffuse the hash state after doing two full rounds above,
-        // whereas simply doing another round of `_mm_aesenc_si128()` does.
-
-        // state = _mm_aesenclast_si128(state, std::mem::transmute(KEYS[7]));
+    let mut state = u128::from_le_bytes(in_bytes.try_into().unwrap());
+    for &key in KEYS.iter().cycle().take(rounds) {
+        state = aesenc(state, key);
     }
 
-    // Copy the mixed state to the output.
-    out_bytes[0..16].copy_from_slice(&unsafe { std::mem::transmute::<_, [u8; 16]>(state) });
-}
-
-pub fn mix_input_1_round(in_bytes: &[u8], out_bytes: &mut [u8]) {
-    mix_input(in_bytes, out_bytes, 1);
-}
+    // Note: `_mm_aesenclast_si128()` doesn't do as much mixing as
+    // `_mm_aesenc_si128()`.  Therfore using it for the last round doesn't
+    // fully diffuse the hash state after doing two full rounds, whereas simply
+    // doing another round of `_mm_aesenc_si128()` does.
 
-pub fn mix_input_2_rounds(in_bytes: &[u8], out_bytes: &mut [u8]) {
-    mix_input(in_bytes, out_bytes, 2);
-}
-
-pub fn mix_input_3_rounds(in_bytes: &[u8], out_bytes: &mut [u8]) {
-    mix_input(in_bytes, out_bytes, 3);
+    // Copy the mixed state to the output.
+    out_bytes[0..16].copy_from_slice(&u128::to_le_bytes(state));
 }
@@ -0,0 +1,67 @@
+//! The Ascon permutation.
+
+pub const IN_SIZE_BYTES: usize = 320 / 8;
+pub const OUT_SIZE_BYTES: usize = 320 / 8;
+/// Ascon-Hash's rate, which is what each squeeze outputs.
+pub const DIGEST_SIZE_BYTES: usize = 64 / 8;
+
+/// The number of rounds in Ascon's p^a, used for initialization and
+/// finalization.  Its p^b uses 6 or 8.
+pub const DEFAULT_ROUNDS: usize = 12;
+
+/// Ascon's permutation with `rounds` rounds on the full state.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    assert!(in_bytes.len() == IN_SIZE_BYTES);
+    assert!(out_bytes.len() == OUT_SIZE_BYTES);
+
+    // Copy the state into the right layout.
+    let mut state = [
+        u64::from_le_bytes((&in_bytes[0..8]).try_into().unwrap()),
+        u64::from_le_bytes((&in_bytes[8..16]).try_into().unwrap()),
+        u64::from_le_bytes((&in_bytes[16..24]).try_into().unwrap()),
+        u64::from_le_bytes((&in_bytes[24..32]).try_into().unwrap()),
+        u64::from_le_bytes((&in_bytes[32..40]).try_into().unwrap()),
+    ];
+
+    permute(&mut state, rounds);
+
+    // Copy the mixed state to the output.
+    out_bytes[0..8].copy_from_slice(&u64::to_le_bytes(state[0]));
+    out_bytes[8..16].copy_from_slice(&u64::to_le_bytes(state[1]));
+    out_bytes[16..24].copy_from_slice(&u64::to_le_bytes(state[2]));
+    out_bytes[24..32].copy_from_slice(&u64::to_le_bytes(state[3]));
+    out_bytes[32..40].copy_from_slice(&u64::to_le_bytes(state[4]));
+}
+
+/// The Ascon permutation with `rounds` rounds.
+///
+/// Like Ascon's p^b, fewer than 12 rounds uses the round constants of the
+/// last rounds of p^a.  More than 12 rounds extends the same constant
+/// pattern backwards, wrapping around after 16.
+pub fn permute(x: &mut [u64; 5], rounds: usize) {
+    for round in 0..rounds {
+        // Constant addition.
+        let i = (DEFAULT_ROUNDS as isize - rounds as isize + round as isize).rem_euclid(16) as u64;
+        x[2] ^= ((0xf - i) << 4) | i;
+
+        // Substitution layer: the 5-bit S-box, bitsliced.
+        x[0] ^= x[4];
+        x[4] ^= x[3];
+        x[2] ^= x[1];
+        let t: [u64; 5] = std::array::from_fn(|j| !x[j] & x[(j + 1) % 5]);
+        for j in 0..5 {
+            x[j] ^= t[(j + 1) % 5];
+        }
+        x[1] ^= x[0];
+        x[0] ^= x[4];
+        x[3] ^= x[2];
+        x[2] = !x[2];
+
+        // Linear diffusion layer.
+        x[0] ^= x[0].rotate_right(19) ^ x[0].rotate_right(28);
+        x[1] ^= x[1].rotate_right(61) ^ x[1].rotate_right(39);
+        x[2] ^= x[2].rotate_right(1) ^ x[2].rotate_right(6);
+        x[3] ^= x[3].rotate_right(10) ^ x[3].rotate_right(17);
+        x[4] ^= x[4].rotate_right(7) ^ x[4].rotate_right(41);
+    }
+}
@@ -0,0 +1,102 @@
+//! BLAKE2b's compression function.
+
+pub const IN_SIZE_BYTES: usize = 1024 / 8;
+pub const OUT_SIZE_BYTES: usize = 512 / 8;
+pub const DIGEST_SIZE_BYTES: usize = 512 / 8;
+
+/// The number of rounds in BLAKE2b.
+pub const DEFAULT_ROUNDS: usize = 12;
+
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// The message word schedule.  Rounds past the tenth reuse it from the start.
+#[rustfmt::skip]
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// BLAKE2b-512 compressing a single message block.
+///
+/// The input is the message block, and the output is the resulting chaining
+/// state.  The block is treated as the last (and only) block of a 128-byte
+/// message, and `rounds` is the number of compression rounds.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    assert!(in_bytes.len() == IN_SIZE_BYTES);
+    assert!(out_bytes.len() == OUT_SIZE_BYTES);
+
+    // Copy input into the right layout.
+    let block: [u64; 16] = std::array::from_fn(|i| {
+        u64::from_le_bytes((&in_bytes[(i * 8)..((i + 1) * 8)]).try_into().unwrap())
+    });
+
+    // Unkeyed, with a 64-byte digest.
+    let mut h = IV;
+    h[0] ^= 0x01010000 ^ DIGEST_SIZE_BYTES as u64;
+
+    compress(&mut h, &block, IN_SIZE_BYTES as u128, true, rounds);
+
+    // Copy the chaining state to the output.
+    for (i, word) in h.iter().enumerate() {
+        out_bytes[(i * 8)..((i + 1) * 8)].copy_from_slice(&u64::to_le_bytes(*word));
+    }
+}
+
+/// The BLAKE2b compression function, with `rounds` rounds.
+///
+/// `byte_count` is the total number of message bytes through the end of this
+/// block, and `last` is whether this is the final block.
+pub fn compress(h: &mut [u64; 8], block: &[u64; 16], byte_count: u128, last: bool, rounds: usize) {
+    let mut v = [0u64; 16];
+    v[0..8].copy_from_slice(&h[..]);
+    v[8..16].copy_from_slice(&IV);
+    v[12] ^= byte_count as u64;
+    v[13] ^= (byte_count >> 64) as u64;
+    if last {
+        v[14] = !v[14];
+    }
+
+    for s in SIGMA.iter().cycle().take(rounds) {
+        g(&mut v, 0, 4, 8, 12, block[s[0]], block[s[1]]);
+        g(&mut v, 1, 5, 9, 13, block[s[2]], block[s[3]]);
+        g(&mut v, 2, 6, 10, 14, block[s[4]], block[s[5]]);
+        g(&mut v, 3, 7, 11, 15, block[s[6]], block[s[7]]);
+
+        g(&mut v, 0, 5, 10, 15, block[s[8]], block[s[9]]);
+        g(&mut v, 1, 6, 11, 12, block[s[10]], block[s[11]]);
+        g(&mut v, 2, 7, 8, 13, block[s[12]], block[s[13]]);
+        g(&mut v, 3, 4, 9, 14, block[s[14]], block[s[15]]);
+    }
+
+    for i in 0..8 {
+        h[i] ^= v[i] ^ v[i + 8];
+    }
+}
+
+fn g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
@@ -0,0 +1,103 @@
+//! BLAKE3's compression function.
+
+pub const IN_SIZE_BYTES: usize = 512 / 8;
+pub const OUT_SIZE_BYTES: usize = 256 / 8;
+pub const DIGEST_SIZE_BYTES: usize = 256 / 8;
+
+/// The number of rounds in BLAKE3.
+pub const DEFAULT_ROUNDS: usize = 7;
+
+pub const CHUNK_START: u32 = 1 << 0;
+pub const CHUNK_END: u32 = 1 << 1;
+pub const ROOT: u32 = 1 << 3;
+
+pub const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// How the message words are permuted between rounds.
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+/// BLAKE3 compressing a single message block.
+///
+/// The input is the message block, and the output is the resulting chaining
+/// value.  The block is treated as a complete 64-byte message, so it's both
+/// the start and end of the root chunk.  `rounds` is the number of compression
+/// rounds.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    assert!(in_bytes.len() == IN_SIZE_BYTES);
+    assert!(out_bytes.len() == OUT_SIZE_BYTES);
+
+    // Copy input into the right layout.
+    let block: [u32; 16] = std::array::from_fn(|i| {
+        u32::from_le_bytes((&in_bytes[(i * 4)..((i + 1) * 4)]).try_into().unwrap())
+    });
+
+    let state = compress(
+        &IV,
+        &block,
+        0,
+        IN_SIZE_BYTES as u32,
+        CHUNK_START | CHUNK_END | ROOT,
+        rounds,
+    );
+
+    // Copy the chaining value to the output.
+    for (i, word) in state[0..8].iter().enumerate() {
+        out_bytes[(i * 4)..((i + 1) * 4)].copy_from_slice(&u32::to_le_bytes(*word));
+    }
+}
+
+/// The BLAKE3 compression function, with `rounds` rounds.
+///
+/// Returns the full 16-word output, the first 8 words of which are the new
+/// chaining value.
+pub fn compress(
+    chaining_value: &[u32; 8],
+    block: &[u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+    rounds: usize,
+) -> [u32; 16] {
+    #[rustfmt::skip]
+    let mut v = [
+        chaining_value[0], chaining_value[1], chaining_value[2], chaining_value[3],
+        chaining_value[4], chaining_value[5], chaining_value[6], chaining_value[7],
+        IV[0], IV[1], IV[2], IV[3],
+        counter as u32, (counter >> 32) as u32, block_len, flags,
+    ];
+
+    let mut m = *block;
+    for _ in 0..rounds {
+        g(&mut v, 0, 4, 8, 12, m[0], m[1]);
+        g(&mut v, 1, 5, 9, 13, m[2], m[3]);
+        g(&mut v, 2, 6, 10, 14, m[4], m[5]);
+        g(&mut v, 3, 7, 11, 15, m[6], m[7]);
+
+        g(&mut v, 0, 5, 10, 15, m[8], m[9]);
+        g(&mut v, 1, 6, 11, 12, m[10], m[11]);
+        g(&mut v, 2, 7, 8, 13, m[12], m[13]);
+        g(&mut v, 3, 4, 9, 14, m[14], m[15]);
+
+        m = std::array::from_fn(|i| m[MSG_PERMUTATION[i]]);
+    }
+
+    for i in 0..8 {
+        v[i] ^= v[i + 8];
+        v[i + 8] ^= chaining_value[i];
+    }
+
+    v
+}
+
+fn g(v: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, x: u32, y: u32) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(12);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(8);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(7);
+}
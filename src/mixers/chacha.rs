@@ -0,0 +1,53 @@
+//! The ChaCha permutation.
+
+pub const IN_SIZE_BYTES: usize = 512 / 8;
+pub const OUT_SIZE_BYTES: usize = 512 / 8;
+pub const DIGEST_SIZE_BYTES: usize = 512 / 8;
+
+/// The number of double rounds in ChaCha20.  ChaCha8 and ChaCha12 use 4 and 6.
+pub const DEFAULT_ROUNDS: usize = 10;
+
+/// The ChaCha permutation, with `rounds` double rounds (a column round
+/// followed by a diagonal round).
+///
+/// This is just the permutation, without ChaCha's feed-forward of the input
+/// state, since that doesn't contribute to mixing.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    assert!(in_bytes.len() == IN_SIZE_BYTES);
+    assert!(out_bytes.len() == OUT_SIZE_BYTES);
+
+    // Copy the state into the right layout.
+    let mut x: [u32; 16] = std::array::from_fn(|i| {
+        u32::from_le_bytes((&in_bytes[(i * 4)..((i + 1) * 4)]).try_into().unwrap())
+    });
+
+    for _ in 0..rounds {
+        // Column round.
+        quarter_round(&mut x, 0, 4, 8, 12);
+        quarter_round(&mut x, 1, 5, 9, 13);
+        quarter_round(&mut x, 2, 6, 10, 14);
+        quarter_round(&mut x, 3, 7, 11, 15);
+
+        // Diagonal round.
+        quarter_round(&mut x, 0, 5, 10, 15);
+        quarter_round(&mut x, 1, 6, 11, 12);
+        quarter_round(&mut x, 2, 7, 8, 13);
+        quarter_round(&mut x, 3, 4, 9, 14);
+    }
+
+    // Copy the mixed state to the output.
+    for (i, word) in x.iter().enumerate() {
+        out_bytes[(i * 4)..((i + 1) * 4)].copy_from_slice(&u32::to_le_bytes(*word));
+    }
+}
+
+fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    x[a] = x[a].wrapping_add(x[b]);
+    x[d] = (x[d] ^ x[a]).rotate_left(16);
+    x[c] = x[c].wrapping_add(x[d]);
+    x[b] = (x[b] ^ x[c]).rotate_left(12);
+    x[a] = x[a].wrapping_add(x[b]);
+    x[d] = (x[d] ^ x[a]).rotate_left(8);
+    x[c] = x[c].wrapping_add(x[d]);
+    x[b] = (x[b] ^ x[c]).rotate_left(7);
+}
@@ -2,9 +2,30 @@ pub const IN_SIZE_BYTES: usize = 512 / 8;
 pub const OUT_SIZE_BYTES: usize = 448 / 8;
 pub const DIGEST_SIZE_BYTES: usize = 128 / 8;
 
+pub const DEFAULT_ROUNDS: usize = 1;
+
 /// The CityHash128/FarmHash128 accumulator.  (Yes, they are identical.)
-pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
-    assert!(in_bytes.len() == IN_SIZE_BYTES);
+///
+/// `rounds` is the number of blocks absorbed.  The input is one or more
+/// whole blocks, and all blocks past its end are zeros.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    accumulate(in_bytes, out_bytes, rounds, None);
+}
+
+/// `mix_input()`, but starting from `prior_state` rather than the state
+/// CityHash128 derives from the first block and the length, as if partway
+/// through a longer input.  The state has the same layout as the output.
+pub fn mix_input_from_state(
+    prior_state: &[u8],
+    in_bytes: &[u8],
+    out_bytes: &mut [u8],
+    rounds: usize,
+) {
+    accumulate(in_bytes, out_bytes, rounds, Some(prior_state));
+}
+
+fn accumulate(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize, prior_state: Option<&[u8]>) {
+    assert!(!in_bytes.is_empty() && in_bytes.len().is_multiple_of(IN_SIZE_BYTES));
     assert!(out_bytes.len() == OUT_SIZE_BYTES);
 
     const K1: u64 = 0xb492b66fbe98f273;
@@ -17,7 +38,7 @@ pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
     // stream of zeroed out bytes after the initial data.  This is useful for
     // testing additional rounds without interference from other data.
     let fetch64 = |i: usize| -> u64 {
-        if (i + 8) <= IN_SIZE_BYTES {
+        if (i + 8) <= in_bytes.len() {
             u64::from_le_bytes((&in_bytes[i..(i + 8)]).try_into().unwrap())
         } else {
             0
@@ -31,27 +52,48 @@ pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
         let y = fetch64(offset + 16);
         let z = fetch64(offset + 24);
 
-        a += w;
-        b = (b + a + z).rotate_right(21);
+        a = a.wrapping_add(w);
+        b = b.wrapping_add(a).wrapping_add(z).rotate_right(21);
         let c = a;
-        a += x;
-        a += y;
-        b += a.rotate_right(44);
+        a = a.wrapping_add(x);
+        a = a.wrapping_add(y);
+        b = b.wrapping_add(a.rotate_right(44));
 
-        (a + z, b + c)
+        (a.wrapping_add(z), b.wrapping_add(c))
     };
 
-    let rounds = 1; // Set higher to play with how diffussion progresses with more blocks.
-
     let mut v = (0u64, 0u64);
     let mut w = (0u64, 0u64);
     let mut x = SEED1;
     let mut y = SEED2;
-    let mut z = (rounds as u64 * 128 / 8).wrapping_mul(K1);
-    v.0 = (y ^ K1).rotate_right(49) * K1 + fetch64(0);
-    v.1 = (v.0).rotate_right(42) * K1 + fetch64(8);
-    w.0 = (y + z).rotate_right(35) * K1 + x;
-    w.1 = (x + fetch64(88)).rotate_right(53) * K1;
+    let mut z = ((rounds * IN_SIZE_BYTES) as u64).wrapping_mul(K1);
+    if let Some(prior_state) = prior_state {
+        assert!(prior_state.len() == OUT_SIZE_BYTES);
+        let state = |i: usize| u64::from_le_bytes((&prior_state[i..(i + 8)]).try_into().unwrap());
+        v = (state(0), state(8));
+        w = (state(16), state(24));
+        x = state(32);
+        y = state(40);
+        z = state(48);
+    } else {
+        v.0 = (y ^ K1)
+            .rotate_right(49)
+            .wrapping_mul(K1)
+            .wrapping_add(fetch64(0));
+        v.1 = (v.0)
+            .rotate_right(42)
+            .wrapping_mul(K1)
+            .wrapping_add(fetch64(8));
+        w.0 = y
+            .wrapping_add(z)
+            .rotate_right(35)
+            .wrapping_mul(K1)
+            .wrapping_add(x);
+        w.1 = x
+            .wrapping_add(fetch64(88))
+            .rotate_right(53)
+            .wrapping_mul(K1);
+    }
 
     let mut data_offset = 0;
     for _ in 0..rounds {
@@ -60,13 +102,26 @@ pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
         // block of data, so for the purpose of analyzing diffusion
         // between blocks we only want a single loop of this.  Hence why
         // the second unroll has been removed here.
-        x = (x + y + v.0 + fetch64(data_offset + 8)).rotate_right(37) * K1;
-        y = (y + v.1 + fetch64(data_offset + 48)).rotate_right(42) * K1;
+        x = x
+            .wrapping_add(y)
+            .wrapping_add(v.0)
+            .wrapping_add(fetch64(data_offset + 8))
+            .rotate_right(37)
+            .wrapping_mul(K1);
+        y = y
+            .wrapping_add(v.1)
+            .wrapping_add(fetch64(data_offset + 48))
+            .rotate_right(42)
+            .wrapping_mul(K1);
         x ^= w.1;
-        y += v.0 + fetch64(data_offset + 40);
-        z = (z + w.0).rotate_right(33) * K1;
-        v = weak_hash_len_32_with_seeds(data_offset, v.1 * K1, x + w.0);
-        w = weak_hash_len_32_with_seeds(data_offset + 32, z + w.1, y + fetch64(data_offset + 16));
+        y = y.wrapping_add(v.0).wrapping_add(fetch64(data_offset + 40));
+        z = z.wrapping_add(w.0).rotate_right(33).wrapping_mul(K1);
+        v = weak_hash_len_32_with_seeds(data_offset, v.1.wrapping_mul(K1), x.wrapping_add(w.0));
+        w = weak_hash_len_32_with_seeds(
+            data_offset + 32,
+            z.wrapping_add(w.1),
+            y.wrapping_add(fetch64(data_offset + 16)),
+        );
         std::mem::swap(&mut z, &mut x);
         data_offset += 64;
     }
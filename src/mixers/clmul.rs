@@ -0,0 +1,81 @@
+//! Carry-less multiplication, as used by polynomial MACs and some fast hashes.
+//!
+//! The x86 PCLMULQDQ instruction is used when the CPU supports it, and a
+//! portable software implementation is used otherwise.
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+pub const IN_SIZE_BYTES: usize = 128 / 8;
+pub const OUT_SIZE_BYTES: usize = 128 / 8;
+pub const DIGEST_SIZE_BYTES: usize = 128 / 8;
+
+/// A single carry-less multiply.
+pub const DEFAULT_ROUNDS: usize = 1;
+
+// Some random numbers that the inputs are xored with before multiplying, the
+// same way the `mum` mixer does.  Without this, a zero in either input would
+// zero out the product.
+const SECRET: [u64; 2] = [0x7c15a1e3b9d04f68, 0xe2f8346a0b5dc917];
+
+/// The 64x64->128 carry-less multiply of the two input words (xored with
+/// secret words).
+///
+/// `rounds` is the number of multiplies.  Each multiply after the first is on
+/// the two halves of the previous product (xored with the secret words again).
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    assert!(in_bytes.len() == IN_SIZE_BYTES);
+    assert!(out_bytes.len() == OUT_SIZE_BYTES);
+
+    let mut a = u64::from_le_bytes((&in_bytes[0..8]).try_into().unwrap());
+    let mut b = u64::from_le_bytes((&in_bytes[8..16]).try_into().unwrap());
+
+    for _ in 0..rounds {
+        let product = clmul(a ^ SECRET[0], b ^ SECRET[1]);
+        a = product as u64;
+        b = (product >> 64) as u64;
+    }
+
+    // Copy the product to the output.
+    out_bytes[0..8].copy_from_slice(&u64::to_le_bytes(a));
+    out_bytes[8..16].copy_from_slice(&u64::to_le_bytes(b));
+}
+
+/// The 64x64->128 carry-less product of `a` and `b`.
+pub fn clmul(a: u64, b: u64) -> u128 {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if std::is_x86_feature_detected!("pclmulqdq") {
+        return unsafe { clmul_hw(a, b) };
+    }
+
+    clmul_soft(a, b)
+}
+
+/// `clmul()` using the x86 PCLMULQDQ instruction.
+///
+/// # Safety
+///
+/// The CPU must support the `pclmulqdq` target feature.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "pclmulqdq")]
+pub unsafe fn clmul_hw(a: u64, b: u64) -> u128 {
+    let a = _mm_loadu_si128((a as u128).to_le_bytes().as_ptr().cast());
+    let b = _mm_loadu_si128((b as u128).to_le_bytes().as_ptr().cast());
+
+    let mut out = [0u8; 16];
+    _mm_storeu_si128(out.as_mut_ptr().cast(), _mm_clmulepi64_si128(a, b, 0x00));
+    u128::from_le_bytes(out)
+}
+
+/// `clmul()` in portable software.
+pub fn clmul_soft(a: u64, b: u64) -> u128 {
+    let mut product = 0u128;
+    for i in 0..64 {
+        if (b >> i) & 1 != 0 {
+            product ^= (a as u128) << i;
+        }
+    }
+    product
+}
@@ -0,0 +1,71 @@
+//! CRC32C, as used by CRC-based hashes.
+//!
+//! The x86 SSE 4.2 CRC32 instruction is used when the CPU supports it, and a
+//! portable software implementation is used otherwise.
+
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+pub const IN_SIZE_BYTES: usize = 64 / 8;
+pub const OUT_SIZE_BYTES: usize = 32 / 8;
+pub const DIGEST_SIZE_BYTES: usize = 32 / 8;
+
+pub const DEFAULT_ROUNDS: usize = 1;
+
+/// The reversed CRC32C (Castagnoli) polynomial.
+const POLY: u32 = 0x82f63b78;
+
+/// A CRC32C accumulator, absorbing 64 bits at a time like the `crc32`
+/// instruction.
+///
+/// `rounds` is the number of words absorbed.  The input is one or more
+/// words, and all words past its end are zeros.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    assert!(!in_bytes.is_empty() && in_bytes.len().is_multiple_of(IN_SIZE_BYTES));
+    assert!(out_bytes.len() == OUT_SIZE_BYTES);
+
+    let words = in_bytes
+        .chunks_exact(IN_SIZE_BYTES)
+        .map(|word| u64::from_le_bytes(word.try_into().unwrap()));
+
+    let mut crc = !0u32;
+    for word in words.chain(std::iter::repeat(0)).take(rounds) {
+        crc = crc32c_u64(crc, word);
+    }
+
+    // Copy the CRC to the output.
+    out_bytes.copy_from_slice(&u32::to_le_bytes(crc));
+}
+
+/// Updates `crc` with the 8 little-endian bytes of `data`, without the
+/// pre- and post-inversion of the full CRC32C.
+pub fn crc32c_u64(crc: u32, data: u64) -> u32 {
+    #[cfg(target_arch = "x86_64")]
+    if std::is_x86_feature_detected!("sse4.2") {
+        return unsafe { crc32c_u64_hw(crc, data) };
+    }
+
+    crc32c_u64_soft(crc, data)
+}
+
+/// `crc32c_u64()` using the x86 SSE 4.2 CRC32 instruction.
+///
+/// # Safety
+///
+/// The CPU must support the `sse4.2` target feature.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.2")]
+pub unsafe fn crc32c_u64_hw(crc: u32, data: u64) -> u32 {
+    _mm_crc32_u64(crc as u64, data) as u32
+}
+
+/// `crc32c_u64()` in portable software.
+pub fn crc32c_u64_soft(mut crc: u32, data: u64) -> u32 {
+    for byte in data.to_le_bytes() {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (POLY & (crc & 1).wrapping_neg());
+        }
+    }
+    crc
+}
@@ -0,0 +1,132 @@
+//! Hash finalizers.
+//!
+//! These are the (mostly) 64-bit bijective mixers that hashes use to finish
+//! off their state, and that hash tables use on integer keys.  Each is
+//! available both as a plain function and as a mixer that applies it `rounds`
+//! times.
+
+pub const IN_SIZE_BYTES: usize = 64 / 8;
+pub const OUT_SIZE_BYTES: usize = 64 / 8;
+pub const DIGEST_SIZE_BYTES: usize = 64 / 8;
+
+/// CityHash's `HashLen16()` takes two 64-bit words.
+pub const HASH_LEN_16_IN_SIZE_BYTES: usize = 128 / 8;
+
+pub const DEFAULT_ROUNDS: usize = 1;
+
+/// Murmur3's `fmix64()`.
+pub fn fmix64(n: u64) -> u64 {
+    stafford(n, 33, 0xff51afd7ed558ccd, 33, 0xc4ceb9fe1a85ec53, 33)
+}
+
+/// The SplitMix64 output function: add the golden gamma, then Mix13.
+pub fn splitmix64(n: u64) -> u64 {
+    stafford_mix13(n.wrapping_add(0x9e3779b97f4a7c15))
+}
+
+// David Stafford's variants of `fmix64()` with better constants:
+// http://zimbry.blogspot.ch/2011/09/better-bit-mixing-improving-on.html
+
+/// Stafford's "Mix01".
+pub fn stafford_mix01(n: u64) -> u64 {
+    stafford(n, 31, 0x7fb5d329728ea185, 27, 0x81dadef4bc2dd44d, 33)
+}
+
+/// Stafford's "Mix04", which Java's `SplittableRandom` uses for 32-bit output.
+pub fn stafford_mix04(n: u64) -> u64 {
+    stafford(n, 33, 0x62a9d9ed799705f5, 28, 0xcb24d0a5c88c35b3, 32)
+}
+
+/// Stafford's "Mix13", which SplitMix64 uses.
+pub fn stafford_mix13(n: u64) -> u64 {
+    stafford(n, 30, 0xbf58476d1ce4e5b9, 27, 0x94d049bb133111eb, 31)
+}
+
+/// Stafford's "Mix14".
+pub fn stafford_mix14(n: u64) -> u64 {
+    stafford(n, 30, 0x4be98134a5976fd3, 29, 0x3bc0993a5ad19a13, 31)
+}
+
+/// Pelle Evensen's Moremur, an `fmix64()` variant with yet more tuned
+/// constants.
+pub fn moremur(n: u64) -> u64 {
+    stafford(n, 27, 0x3c79ac492ba7b653, 33, 0x1c69b3f74ac4ae35, 27)
+}
+
+/// xxHash64's final avalanche.
+pub fn xxh64_avalanche(n: u64) -> u64 {
+    stafford(n, 33, 0xc2b2ae3d27d4eb4f, 29, 0x165667b19e3779f9, 32)
+}
+
+/// XXH3's final avalanche, which is cheaper than xxHash64's since it's
+/// applied to an already well-mixed accumulator.
+pub fn xxh3_avalanche(mut n: u64) -> u64 {
+    n ^= n >> 37;
+    n = n.wrapping_mul(0x165667919e3779f9);
+    n ^ (n >> 32)
+}
+
+/// CityHash's `HashLen16()`, which mixes two 64-bit words down to one.
+pub fn city_hash_len_16(u: u64, v: u64) -> u64 {
+    const MUL: u64 = 0x9ddfea08eb382d69;
+    let mut a = (u ^ v).wrapping_mul(MUL);
+    a ^= a >> 47;
+    let mut b = (v ^ a).wrapping_mul(MUL);
+    b ^= b >> 47;
+    b.wrapping_mul(MUL)
+}
+
+/// The xorshift-multiply-xorshift-multiply-xorshift structure shared by
+/// `fmix64()` and its variants.
+fn stafford(mut n: u64, s1: u32, m1: u64, s2: u32, m2: u64, s3: u32) -> u64 {
+    n ^= n >> s1;
+    n = n.wrapping_mul(m1);
+    n ^= n >> s2;
+    n = n.wrapping_mul(m2);
+    n ^ (n >> s3)
+}
+
+/// Defines a mixer that applies a 64-bit finalizer `rounds` times.
+macro_rules! finalizer_mixer {
+    ($name:ident, $finalizer:ident) => {
+        #[doc = concat!("`", stringify!($finalizer), "()` as a mixer.")]
+        pub fn $name(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+            assert!(in_bytes.len() == IN_SIZE_BYTES);
+            assert!(out_bytes.len() == OUT_SIZE_BYTES);
+
+            let mut n = u64::from_le_bytes(in_bytes.try_into().unwrap());
+            for _ in 0..rounds {
+                n = $finalizer(n);
+            }
+
+            out_bytes.copy_from_slice(&u64::to_le_bytes(n));
+        }
+    };
+}
+
+finalizer_mixer!(mix_fmix64, fmix64);
+finalizer_mixer!(mix_splitmix64, splitmix64);
+finalizer_mixer!(mix_stafford_mix01, stafford_mix01);
+finalizer_mixer!(mix_stafford_mix04, stafford_mix04);
+finalizer_mixer!(mix_stafford_mix13, stafford_mix13);
+finalizer_mixer!(mix_stafford_mix14, stafford_mix14);
+finalizer_mixer!(mix_moremur, moremur);
+finalizer_mixer!(mix_xxh64_avalanche, xxh64_avalanche);
+finalizer_mixer!(mix_xxh3_avalanche, xxh3_avalanche);
+
+/// `city_hash_len_16()` as a mixer.
+///
+/// Rounds after the first mix the previous result with the original second
+/// word again.
+pub fn mix_city_hash_len_16(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    assert!(in_bytes.len() == HASH_LEN_16_IN_SIZE_BYTES);
+    assert!(out_bytes.len() == OUT_SIZE_BYTES);
+
+    let mut u = u64::from_le_bytes((&in_bytes[0..8]).try_into().unwrap());
+    let v = u64::from_le_bytes((&in_bytes[8..16]).try_into().unwrap());
+    for _ in 0..rounds {
+        u = city_hash_len_16(u, v);
+    }
+
+    out_bytes.copy_from_slice(&u64::to_le_bytes(u));
+}
@@ -2,11 +2,13 @@ pub const IN_SIZE_BYTES: usize = 128 / 8;
 pub const OUT_SIZE_BYTES: usize = 128 / 8;
 pub const DIGEST_SIZE_BYTES: usize = 128 / 8;
 
-// Play with this to see how the number of rounds affects diffusion.
-const ROUNDS: usize = 12;
+/// The number of rounds used in "Hash Design and Goodhart's Law".
+pub const DEFAULT_ROUNDS: usize = 12;
 
 /// The mix function from "Hash Design and Goodhart's Law".
-pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
+///
+/// The rotation constants repeat after 16 rounds.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
     assert!(in_bytes.len() == IN_SIZE_BYTES);
     assert!(out_bytes.len() == OUT_SIZE_BYTES);
 
@@ -18,7 +20,7 @@ pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
 
     const ROTATIONS: &[u32] = &[12, 39, 21, 13, 32, 11, 24, 53, 17, 27, 57, 13, 50, 8, 52, 8];
 
-    for &rot in ROTATIONS.iter().take(ROUNDS) {
+    for &rot in ROTATIONS.iter().cycle().take(rounds) {
         state[0] = state[0].wrapping_add(state[1]).wrapping_add(1);
         state[1] = state[1].rotate_left(rot) ^ state[0];
     }
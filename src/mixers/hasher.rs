@@ -0,0 +1,98 @@
+//! An adapter for using any `std::hash::BuildHasher` as a mixer, so that the
+//! hashers used in practice can be measured end to end without porting their
+//! internals.
+
+use std::hash::{BuildHasher, Hash, Hasher};
+
+/// A typical small hash map key.
+pub const IN_SIZE_BYTES: usize = 128 / 8;
+/// A single `finish()` result.
+pub const OUT_SIZE_BYTES: usize = 64 / 8;
+/// Four `finish()` results under different seeds.
+pub const WIDE_OUT_SIZE_BYTES: usize = 256 / 8;
+pub const DIGEST_SIZE_BYTES: usize = 64 / 8;
+
+pub const DEFAULT_ROUNDS: usize = 1;
+
+/// How the input bytes are fed to the hasher.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WriteStrategy {
+    /// A single `write()` of the whole input.
+    Write,
+    /// A `write_u8()` per byte.
+    WriteU8,
+    /// A `write_u32()` per little-endian 32-bit word.
+    WriteU32,
+    /// A `write_u64()` per little-endian 64-bit word.
+    WriteU64,
+    /// A `write_u128()` per little-endian 128-bit word.
+    WriteU128,
+    /// Hashing the input as a `&[u8]`, the way a hash map with byte slice
+    /// keys does.  This also writes the length.
+    Hash,
+}
+
+impl WriteStrategy {
+    /// The number of input bytes consumed by each write.  The input size must
+    /// be a multiple of this.
+    pub fn word_size(self) -> usize {
+        match self {
+            WriteStrategy::Write | WriteStrategy::WriteU8 | WriteStrategy::Hash => 1,
+            WriteStrategy::WriteU32 => 4,
+            WriteStrategy::WriteU64 => 8,
+            WriteStrategy::WriteU128 => 16,
+        }
+    }
+
+    fn write<H: Hasher>(self, hasher: &mut H, bytes: &[u8]) {
+        match self {
+            WriteStrategy::Write => hasher.write(bytes),
+            WriteStrategy::WriteU8 => bytes.iter().for_each(|&b| hasher.write_u8(b)),
+            WriteStrategy::WriteU32 => bytes
+                .chunks_exact(4)
+                .for_each(|w| hasher.write_u32(u32::from_le_bytes(w.try_into().unwrap()))),
+            WriteStrategy::WriteU64 => bytes
+                .chunks_exact(8)
+                .for_each(|w| hasher.write_u64(u64::from_le_bytes(w.try_into().unwrap()))),
+            WriteStrategy::WriteU128 => bytes
+                .chunks_exact(16)
+                .for_each(|w| hasher.write_u128(u128::from_le_bytes(w.try_into().unwrap()))),
+            WriteStrategy::Hash => bytes.hash(hasher),
+        }
+    }
+}
+
+/// Hashes the input with a hasher from `B::default()`, and outputs the
+/// `finish()` result.
+///
+/// The input can be any multiple of the strategy's word size.  The output can
+/// be any multiple of 8 bytes: each 8 bytes is a separate hash of the input
+/// under a different seed, to widen the output.  The first is unseeded, and
+/// seed `i` is applied by a `write_u64(i)` before the input, since
+/// `BuildHasher` has no general way to seed.
+///
+/// `rounds` is the number of times the input is written, with all writes
+/// after the first being zeros.
+pub fn mix_input<B: BuildHasher + Default>(
+    in_bytes: &[u8],
+    out_bytes: &mut [u8],
+    rounds: usize,
+    write: WriteStrategy,
+) {
+    assert!(in_bytes.len().is_multiple_of(write.word_size()));
+    assert!(out_bytes.len().is_multiple_of(8));
+
+    let build_hasher = B::default();
+    let zeros = vec![0u8; in_bytes.len()];
+
+    for (seed, out_word) in out_bytes.chunks_exact_mut(8).enumerate() {
+        let mut hasher = build_hasher.build_hasher();
+        if seed > 0 {
+            hasher.write_u64(seed as u64);
+        }
+        for round in 0..rounds {
+            write.write(&mut hasher, if round == 0 { in_bytes } else { &zeros });
+        }
+        out_word.copy_from_slice(&u64::to_le_bytes(hasher.finish()));
+    }
+}
@@ -0,0 +1,110 @@
+//! HighwayHash's update, in its portable form.
+
+pub const IN_SIZE_BYTES: usize = 256 / 8;
+pub const OUT_SIZE_BYTES: usize = 1024 / 8;
+pub const DIGEST_SIZE_BYTES: usize = 64 / 8;
+
+pub const DEFAULT_ROUNDS: usize = 1;
+
+/// HighwayHash's state: `[v0, v1, mul0, mul1]`.
+pub type State = [[u64; 4]; 4];
+
+// Some random numbers to use as the key for the mixer.
+const KEY: [u64; 4] = [
+    0x9a3c5b1e7d24f068,
+    0x2e81d4a6c0f3b957,
+    0x64f0b2893ec7a51d,
+    0xd1a7386e5b02c4f9,
+];
+
+/// The HighwayHash packet absorber.
+///
+/// `rounds` is the number of packets absorbed.  The input is one or more
+/// whole packets, and all packets past its end are zeros.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    assert!(!in_bytes.is_empty() && in_bytes.len().is_multiple_of(IN_SIZE_BYTES));
+    assert!(out_bytes.len() == OUT_SIZE_BYTES);
+
+    let mut state = reset(&KEY);
+    absorb(&mut state, in_bytes, rounds);
+
+    // Copy the mixed state to the output.
+    for (i, word) in state.iter().flatten().enumerate() {
+        out_bytes[(i * 8)..((i + 1) * 8)].copy_from_slice(&u64::to_le_bytes(*word));
+    }
+}
+
+/// Absorbs `rounds` packets of `in_bytes` into `state`, with all packets
+/// past its end being zeros.
+pub fn absorb(state: &mut State, in_bytes: &[u8], rounds: usize) {
+    // Copy input into the right layout, with zeros past the end.
+    let packets = in_bytes.chunks_exact(IN_SIZE_BYTES).map(|packet| {
+        std::array::from_fn(|i| {
+            u64::from_le_bytes((&packet[(i * 8)..((i + 1) * 8)]).try_into().unwrap())
+        })
+    });
+
+    for packet in packets.chain(std::iter::repeat([0; 4])).take(rounds) {
+        update(state, &packet);
+    }
+}
+
+/// Initializes the state from a key.
+pub fn reset(key: &[u64; 4]) -> State {
+    let mul0 = [
+        0xdbe6d5d5fe4cce2f,
+        0xa4093822299f31d0,
+        0x13198a2e03707344,
+        0x243f6a8885a308d3,
+    ];
+    let mul1 = [
+        0x3bd39e10cb0ef593,
+        0xc0acf169b5f18a8c,
+        0xbe5466cf34e90c6c,
+        0x452821e638d01377,
+    ];
+
+    let v0 = std::array::from_fn(|i| mul0[i] ^ key[i]);
+    let v1 = std::array::from_fn(|i| mul1[i] ^ key[i].rotate_left(32));
+    [v0, v1, mul0, mul1]
+}
+
+/// Absorbs one 32-byte packet, as four 64-bit lanes.
+pub fn update(state: &mut State, lanes: &[u64; 4]) {
+    let [v0, v1, mul0, mul1] = state;
+
+    for i in 0..4 {
+        v1[i] = v1[i].wrapping_add(mul0[i].wrapping_add(lanes[i]));
+        mul0[i] ^= (v1[i] & 0xffffffff).wrapping_mul(v0[i] >> 32);
+        v0[i] = v0[i].wrapping_add(mul1[i]);
+        mul1[i] ^= (v0[i] & 0xffffffff).wrapping_mul(v1[i] >> 32);
+    }
+
+    zipper_merge_and_add(v1[1], v1[0], v0, 1, 0);
+    zipper_merge_and_add(v1[3], v1[2], v0, 3, 2);
+    zipper_merge_and_add(v0[1], v0[0], v1, 1, 0);
+    zipper_merge_and_add(v0[3], v0[2], v1, 3, 2);
+}
+
+/// Shuffles the bytes of `v1` and `v0` so that the bytes with the most
+/// entropy from the multiplies end up where they'll be multiplied next, and
+/// adds the result to `add[i1]` and `add[i0]`.
+fn zipper_merge_and_add(v1: u64, v0: u64, add: &mut [u64; 4], i1: usize, i0: usize) {
+    add[i0] = add[i0].wrapping_add(
+        (((v0 & 0xff000000) | (v1 & 0xff00000000)) >> 24)
+            | (((v0 & 0xff0000000000) | (v1 & 0xff000000000000)) >> 16)
+            | (v0 & 0xff0000)
+            | ((v0 & 0xff00) << 32)
+            | ((v1 & 0xff00000000000000) >> 8)
+            | (v0 << 56),
+    );
+    add[i1] = add[i1].wrapping_add(
+        (((v1 & 0xff000000) | (v0 & 0xff00000000)) >> 24)
+            | (v1 & 0xff0000)
+            | ((v1 & 0xff0000000000) >> 16)
+            | ((v1 & 0xff00) << 24)
+            | ((v0 & 0xff000000000000) >> 8)
+            | ((v1 & 0xff) << 48)
+            | (v0 & 0xff00000000000000),
+    );
+}
@@ -0,0 +1,145 @@
+//! The Keccak-f permutations, in the 1600-bit size used by SHA-3 and the
+//! smaller 400-bit size.
+
+pub const IN_SIZE_BYTES: usize = 1600 / 8;
+pub const OUT_SIZE_BYTES: usize = 1600 / 8;
+/// SHA3-256's output size.
+pub const DIGEST_SIZE_BYTES: usize = 256 / 8;
+
+/// The number of rounds in Keccak-f[1600].
+pub const DEFAULT_ROUNDS: usize = 24;
+
+pub const IN_SIZE_BYTES_400: usize = 400 / 8;
+pub const OUT_SIZE_BYTES_400: usize = 400 / 8;
+pub const DIGEST_SIZE_BYTES_400: usize = 128 / 8;
+
+/// The number of rounds in Keccak-f[400].
+pub const DEFAULT_ROUNDS_400: usize = 20;
+
+/// The rho rotation offsets, indexed by `x + 5 * y`.  For the smaller lane
+/// sizes these are taken modulo the lane size, which `rotate_left()` does
+/// anyway.
+const ROTATIONS: [u32; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// The iota round constants for every round index.  These repeat with a
+/// period of 255, and the smaller lane sizes use them truncated.
+///
+/// Keccak-p with fewer rounds than Keccak-f uses the *last* rounds of
+/// Keccak-f, and with more rounds it extends backwards, so the round indices
+/// can be "negative" and are taken modulo the period.
+const ROUND_CONSTANTS: [u64; 255] = round_constants();
+
+/// Keccak-f[1600] with `rounds` rounds (i.e. Keccak-p[1600, rounds]) on the
+/// full state.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    assert!(in_bytes.len() == IN_SIZE_BYTES);
+    assert!(out_bytes.len() == OUT_SIZE_BYTES);
+
+    // Copy the state into the right layout.
+    let mut state: [u64; 25] = std::array::from_fn(|i| {
+        u64::from_le_bytes((&in_bytes[(i * 8)..((i + 1) * 8)]).try_into().unwrap())
+    });
+
+    keccak_f1600(&mut state, rounds);
+
+    // Copy the mixed state to the output.
+    for (i, lane) in state.iter().enumerate() {
+        out_bytes[(i * 8)..((i + 1) * 8)].copy_from_slice(&u64::to_le_bytes(*lane));
+    }
+}
+
+/// Keccak-f[400] with `rounds` rounds (i.e. Keccak-p[400, rounds]) on the
+/// full state.
+pub fn mix_input_400(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    assert!(in_bytes.len() == IN_SIZE_BYTES_400);
+    assert!(out_bytes.len() == OUT_SIZE_BYTES_400);
+
+    // Copy the state into the right layout.
+    let mut state: [u16; 25] = std::array::from_fn(|i| {
+        u16::from_le_bytes((&in_bytes[(i * 2)..((i + 1) * 2)]).try_into().unwrap())
+    });
+
+    keccak_f400(&mut state, rounds);
+
+    // Copy the mixed state to the output.
+    for (i, lane) in state.iter().enumerate() {
+        out_bytes[(i * 2)..((i + 1) * 2)].copy_from_slice(&u16::to_le_bytes(*lane));
+    }
+}
+
+macro_rules! keccak_f {
+    ($name:ident, $lane:ty, $default_rounds:expr) => {
+        /// The Keccak-f permutation on a state of 25 lanes, indexed by
+        /// `x + 5 * y`.
+        pub fn $name(state: &mut [$lane; 25], rounds: usize) {
+            let first_round = $default_rounds as isize - rounds as isize;
+
+            for round in 0..rounds {
+                // Theta.
+                let c: [$lane; 5] = std::array::from_fn(|x| {
+                    state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20]
+                });
+                for x in 0..5 {
+                    let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+                    for y in 0..5 {
+                        state[x + 5 * y] ^= d;
+                    }
+                }
+
+                // Rho and pi.
+                let mut b = [0; 25];
+                for x in 0..5 {
+                    for y in 0..5 {
+                        b[y + 5 * ((2 * x + 3 * y) % 5)] =
+                            state[x + 5 * y].rotate_left(ROTATIONS[x + 5 * y]);
+                    }
+                }
+
+                // Chi.
+                for y in 0..5 {
+                    for x in 0..5 {
+                        state[x + 5 * y] =
+                            b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+                    }
+                }
+
+                // Iota.
+                let index = (first_round + round as isize).rem_euclid(255) as usize;
+                state[0] ^= ROUND_CONSTANTS[index] as $lane;
+            }
+        }
+    };
+}
+
+keccak_f!(keccak_f1600, u64, DEFAULT_ROUNDS);
+keccak_f!(keccak_f400, u16, DEFAULT_ROUNDS_400);
+
+/// Computes the round constants with the LFSR from the Keccak reference.
+const fn round_constants() -> [u64; 255] {
+    // The LFSR output bits, `rc(t)` in the reference.
+    let mut rc = [0u64; 255];
+    let mut r: u16 = 1;
+    let mut t = 0;
+    while t < 255 {
+        rc[t] = (r & 1) as u64;
+        r <<= 1;
+        if r & 0x100 != 0 {
+            r ^= 0x171;
+        }
+        t += 1;
+    }
+
+    let mut constants = [0u64; 255];
+    let mut round = 0;
+    while round < 255 {
+        let mut j = 0;
+        while j < 7 {
+            constants[round] |= rc[(j + 7 * round) % 255] << ((1 << j) - 1);
+            j += 1;
+        }
+        round += 1;
+    }
+    constants
+}
@@ -4,10 +4,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use std::sync::atomic::{fence, Ordering};
-
-// Defined below.
-use x86::*;
+use crate::aes_round::aesdec;
 
 pub const IN_SIZE_BYTES: usize = 256 / 8;
 pub const OUT_SIZE_BYTES: usize = 1024 / 8;
@@ -26,195 +23,94 @@ const SEED: [[u8; 16]; OUT_SIZE_BYTES / 16] = [
 ];
 
 macro_rules! mix_reg {
-    ($r1:ident, $r2:ident, $r3:ident, $r4:ident, $r5:ident, $i1:expr, $i2:expr, $i3:expr, $i4:expr) => {
+    ($r1:expr, $r2:expr, $r3:expr, $r4:expr, $r5:expr, $i1:expr, $i2:expr, $i3:expr, $i4:expr) => {
         $r1 = aesdec($r1, $r2);
-        fence(Ordering::AcqRel);
         $r3 = paddq($r3, $i1);
-        $r2 = pxor($r2, $i2);
+        $r2 ^= $i2;
         $r2 = aesdec($r2, $r4);
-        fence(Ordering::AcqRel);
         $r5 = paddq($r5, $i3);
-        $r4 = pxor($r4, $i4);
+        $r4 ^= $i4;
     };
 }
 
 macro_rules! mix {
-    ($r1:ident, $r2:ident, $r3:ident, $r4:ident, $r5:ident, $ptr:expr) => {
+    ($r1:expr, $r2:expr, $r3:expr, $r4:expr, $r5:expr, $block:expr) => {
         mix_reg!(
             $r1,
             $r2,
             $r3,
             $r4,
             $r5,
-            movdqu(($ptr).add(15).cast()),
-            movdqu(($ptr).add(0).cast()),
-            movdqu(($ptr).add(1).cast()),
-            movdqu(($ptr).add(16).cast())
+            movdqu($block, 15),
+            movdqu($block, 0),
+            movdqu($block, 1),
+            movdqu($block, 16)
         )
     };
 }
 
+/// We're being a bit generous here by considering a round to be three mix
+/// calls (actually incorporating three blocks) rather than one.  The
+/// rationale is that due to the way MeowHash shuffles its use of the xmm
+/// slots, this is the minimum number of mix calls needed to ensure that every
+/// xmm slot will have another input block incorporated into it (it's actually
+/// the fourth mix call that touches the final xmm slot, but it does so before
+/// doing any mixing on that slot).  Since what we care about is the complexity
+/// of inter-block bit relationships, that's what's needed here to be minimally
+/// pessimistic.
+///
+/// Note that it's possible the situation is worse than this test indicates,
+/// but asserting that would require more analysis than I have the energy for.
+///
+/// Regardless, it's a bit moot since an input block doesn't reach even close
+/// to 128 bits of min diffusion within three mix calls anyway.  That takes six
+/// mix calls (and more for patterned inputs).
+pub const DEFAULT_ROUNDS: usize = 3;
+
 /// The MeowHash v0.5 block absorber.
-pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
-    assert!(in_bytes.len() == IN_SIZE_BYTES);
+///
+/// `rounds` is the number of mix calls, each of which absorbs one block.  The
+/// input is one or more whole blocks, and all blocks past its end are zeros.
+/// The xmm slots used by each mix call rotate with a period of eight mix
+/// calls.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    assert!(!in_bytes.is_empty() && in_bytes.len().is_multiple_of(IN_SIZE_BYTES));
     assert!(out_bytes.len() == OUT_SIZE_BYTES);
 
     let zero_bytes = [0u8; IN_SIZE_BYTES];
 
-    let mut xmm0: Simd128 = unsafe { std::mem::transmute(SEED[0]) };
-    let mut xmm1: Simd128 = unsafe { std::mem::transmute(SEED[1]) };
-    let mut xmm2: Simd128 = unsafe { std::mem::transmute(SEED[2]) };
-    let mut xmm3: Simd128 = unsafe { std::mem::transmute(SEED[3]) };
-    let mut xmm4: Simd128 = unsafe { std::mem::transmute(SEED[4]) };
-    let mut xmm5: Simd128 = unsafe { std::mem::transmute(SEED[5]) };
-    let mut xmm6: Simd128 = unsafe { std::mem::transmute(SEED[6]) };
-    let mut xmm7: Simd128 = unsafe { std::mem::transmute(SEED[7]) };
+    let mut xmm: [u128; 8] = std::array::from_fn(|i| u128::from_le_bytes(SEED[i]));
 
     // The absorber.
-    //
-    // We're being a bit generous here by considering a round to be three
-    // mix calls (actually incorporating three blocks) rather than one.  The
-    // rationale is that due to the way MeowHash shuffles its use of the xmm
-    // slots, this is the minimum number of mix calls needed to ensure that
-    // every xmm slot will have another input block incorporated into it (it's
-    // actually the fourth mix call that touches the final xmm slot, but it does
-    // so before doing any mixing on that slot).  Since what we care about is
-    // the complexity of inter-block bit relationships, that's what's needed
-    // here to be minimally pessimistic.
-    //
-    // Note that it's possible the situation is worse than this test indicates,
-    // but asserting that would require more analysis than I have the energy
-    // for.
-    //
-    // Regardless, it's a bit moot since an input block doesn't reach even close
-    // to 128 bits of min diffusion within three mix calls anyway.  That takes
-    // six mix calls (and more for patterned inputs).
-    //
-    // You can uncomment subsequent rounds below to play with how much diffusion
-    // you get per subsequent input block.
-    unsafe {
-        // Round 1.
-        mix!(xmm0, xmm4, xmm6, xmm1, xmm2, in_bytes.as_ptr());
-        mix!(xmm1, xmm5, xmm7, xmm2, xmm3, zero_bytes.as_ptr());
-        mix!(xmm2, xmm6, xmm0, xmm3, xmm4, zero_bytes.as_ptr());
-
-        // // Round 2.
-        // mix!(xmm3, xmm7, xmm1, xmm4, xmm5, zero_bytes.as_ptr());
-        // mix!(xmm4, xmm0, xmm2, xmm5, xmm6, zero_bytes.as_ptr());
-        // mix!(xmm5, xmm1, xmm3, xmm6, xmm7, zero_bytes.as_ptr());
-
-        // // Round 3.
-        // mix!(xmm6, xmm2, xmm4, xmm7, xmm0, zero_bytes.as_ptr());
-        // mix!(xmm7, xmm3, xmm5, xmm0, xmm1, zero_bytes.as_ptr());
-
-        // Repeat.
-
-        // // Round 1.
-        // mix!(xmm0, xmm4, xmm6, xmm1, xmm2, zero_bytes.as_ptr());
-        // mix!(xmm1, xmm5, xmm7, xmm2, xmm3, zero_bytes.as_ptr());
-        // mix!(xmm2, xmm6, xmm0, xmm3, xmm4, zero_bytes.as_ptr());
-
-        // // Round 2.
-        // mix!(xmm3, xmm7, xmm1, xmm4, xmm5, zero_bytes.as_ptr());
-        // mix!(xmm4, xmm0, xmm2, xmm5, xmm6, zero_bytes.as_ptr());
-        // mix!(xmm5, xmm1, xmm3, xmm6, xmm7, zero_bytes.as_ptr());
-
-        // // Round 3.
-        // mix!(xmm6, xmm2, xmm4, xmm7, xmm0, zero_bytes.as_ptr());
-        // mix!(xmm7, xmm3, xmm5, xmm0, xmm1, zero_bytes.as_ptr());
+    for i in 0..rounds {
+        let offset = i * IN_SIZE_BYTES;
+        let block = in_bytes
+            .get(offset..(offset + IN_SIZE_BYTES))
+            .unwrap_or(&zero_bytes[..]);
+        mix!(
+            xmm[i % 8],
+            xmm[(i + 4) % 8],
+            xmm[(i + 6) % 8],
+            xmm[(i + 1) % 8],
+            xmm[(i + 2) % 8],
+            block
+        );
     }
 
     // Copy the mixed state to the output.
-    out_bytes[0..16].copy_from_slice(&unsafe { std::mem::transmute::<_, [u8; 16]>(xmm0) });
-    out_bytes[16..32].copy_from_slice(&unsafe { std::mem::transmute::<_, [u8; 16]>(xmm1) });
-    out_bytes[32..48].copy_from_slice(&unsafe { std::mem::transmute::<_, [u8; 16]>(xmm2) });
-    out_bytes[48..64].copy_from_slice(&unsafe { std::mem::transmute::<_, [u8; 16]>(xmm3) });
-    out_bytes[64..80].copy_from_slice(&unsafe { std::mem::transmute::<_, [u8; 16]>(xmm4) });
-    out_bytes[80..96].copy_from_slice(&unsafe { std::mem::transmute::<_, [u8; 16]>(xmm5) });
-    out_bytes[96..112].copy_from_slice(&unsafe { std::mem::transmute::<_, [u8; 16]>(xmm6) });
-    out_bytes[112..128].copy_from_slice(&unsafe { std::mem::transmute::<_, [u8; 16]>(xmm7) });
-}
-
-mod x86 {
-    #[cfg(target_arch = "x86")]
-    use core::arch::x86::*;
-    #[cfg(target_arch = "x86_64")]
-    use core::arch::x86_64::*;
-
-    pub(crate) type Simd128 = __m128i;
-
-    // pub(crate) const MEOW_PREFETCH: usize = 4096;
-    // pub(crate) const MEOW_PREFETCH_LIMIT: usize = 0x3ff;
-
-    // #[inline]
-    // #[target_feature(enable = "sse")]
-    // pub(crate) unsafe fn prefetcht0(p: *const u8) {
-    //     _mm_prefetch(p as *const i8, _MM_HINT_T0)
-    // }
-
-    #[inline]
-    #[target_feature(enable = "sse2")]
-    pub(crate) unsafe fn movdqu(addr: *const u8) -> Simd128 {
-        _mm_loadu_si128(addr.cast())
+    for (i, reg) in xmm.iter().enumerate() {
+        out_bytes[(i * 16)..((i + 1) * 16)].copy_from_slice(&u128::to_le_bytes(*reg));
     }
+}
 
-    // #[inline]
-    // #[target_feature(enable = "sse2")]
-    // pub(crate) unsafe fn movdqu_mem(addr: *mut Simd128, value: Simd128) {
-    //     _mm_storeu_si128(addr, value)
-    // }
-
-    // #[inline]
-    // #[target_feature(enable = "sse2")]
-    // pub(crate) unsafe fn movq(value: i64) -> Simd128 {
-    //     _mm_set_epi64x(0, value)
-    // }
-
-    #[inline]
-    #[target_feature(enable = "aes")]
-    pub(crate) unsafe fn aesdec(value: Simd128, key: Simd128) -> Simd128 {
-        _mm_aesdec_si128(value, key)
-    }
-
-    #[inline]
-    #[target_feature(enable = "sse2")]
-    pub(crate) unsafe fn pxor(a: Simd128, b: Simd128) -> Simd128 {
-        _mm_xor_si128(a, b)
-    }
-
-    #[inline]
-    #[target_feature(enable = "sse2")]
-    pub(crate) unsafe fn paddq(a: Simd128, b: Simd128) -> Simd128 {
-        _mm_add_epi64(a, b)
-    }
-
-    // #[inline]
-    // #[target_feature(enable = "sse2")]
-    // pub(crate) unsafe fn pand(a: Simd128, b: Simd128) -> Simd128 {
-    //     _mm_and_si128(a, b)
-    // }
-
-    // #[inline]
-    // #[target_feature(enable = "ssse3")]
-    // pub(crate) unsafe fn palignr_1(a: Simd128, b: Simd128) -> Simd128 {
-    //     _mm_alignr_epi8(a, b, 1)
-    // }
-
-    // #[inline]
-    // #[target_feature(enable = "ssse3")]
-    // pub(crate) unsafe fn palignr_15(a: Simd128, b: Simd128) -> Simd128 {
-    //     _mm_alignr_epi8(a, b, 15)
-    // }
-
-    // #[inline]
-    // #[target_feature(enable = "sse2")]
-    // pub(crate) unsafe fn pxor_clear() -> Simd128 {
-    //     _mm_setzero_si128()
-    // }
+/// Unaligned load of the 16 bytes at `offset`.
+fn movdqu(bytes: &[u8], offset: usize) -> u128 {
+    u128::from_le_bytes((&bytes[offset..(offset + 16)]).try_into().unwrap())
+}
 
-    // #[inline]
-    // #[target_feature(enable = "sse2")]
-    // pub(crate) unsafe fn cmpeq(a: Simd128, b: Simd128) -> bool {
-    //     _mm_movemask_epi8(_mm_cmpeq_epi8(a, b)) == 0xFFFF
-    // }
+/// Lane-wise addition of two pairs of 64-bit integers.
+fn paddq(a: u128, b: u128) -> u128 {
+    let lo = (a as u64).wrapping_add(b as u64);
+    let hi = ((a >> 64) as u64).wrapping_add((b >> 64) as u64);
+    ((hi as u128) << 64) | lo as u128
 }
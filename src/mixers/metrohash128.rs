@@ -2,10 +2,28 @@ pub const IN_SIZE_BYTES: usize = 256 / 8;
 pub const OUT_SIZE_BYTES: usize = 256 / 8;
 pub const DIGEST_SIZE_BYTES: usize = 128 / 8;
 
+pub const DEFAULT_ROUNDS: usize = 1;
+
 /// The MetroHash128 accumulator.
-pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
-    assert!(in_bytes.len() == IN_SIZE_BYTES);
+///
+/// `rounds` is the number of blocks absorbed.  The input is one or more
+/// whole blocks, and all blocks past its end are zeros.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    mix_input_from_state(&[0; OUT_SIZE_BYTES], in_bytes, out_bytes, rounds);
+}
+
+/// `mix_input()`, but starting from `prior_state` rather than zeros, as if
+/// partway through a longer input.  The state has the same layout as the
+/// output.
+pub fn mix_input_from_state(
+    prior_state: &[u8],
+    in_bytes: &[u8],
+    out_bytes: &mut [u8],
+    rounds: usize,
+) {
+    assert!(!in_bytes.is_empty() && in_bytes.len().is_multiple_of(IN_SIZE_BYTES));
     assert!(out_bytes.len() == OUT_SIZE_BYTES);
+    assert!(prior_state.len() == OUT_SIZE_BYTES);
 
     const K0: u64 = 0xC83A91E1;
     const K1: u64 = 0x8648DBDB;
@@ -17,29 +35,33 @@ pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
     // stream of zeroed out bytes after the initial data.  This is useful for
     // testing additional rounds without interference from other data.
     let read_u64 = |i: usize| -> u64 {
-        if (i + 8) <= IN_SIZE_BYTES {
+        if (i + 8) <= in_bytes.len() {
             u64::from_le_bytes((&in_bytes[i..(i + 8)]).try_into().unwrap())
         } else {
             0
         }
     };
 
-    let mut state = [0u64; 4];
+    let mut state = [
+        u64::from_le_bytes((&prior_state[0..8]).try_into().unwrap()),
+        u64::from_le_bytes((&prior_state[8..16]).try_into().unwrap()),
+        u64::from_le_bytes((&prior_state[16..24]).try_into().unwrap()),
+        u64::from_le_bytes((&prior_state[24..32]).try_into().unwrap()),
+    ];
     let mut offset = 0;
-    let rounds = 1; // Set higher to play with how much diffusion you get per block.
     for _ in 0..rounds {
-        state[0] += read_u64(offset) * K0;
+        state[0] = state[0].wrapping_add(read_u64(offset).wrapping_mul(K0));
         offset += 8;
-        state[0] = state[0].rotate_right(29) + state[2];
-        state[1] += read_u64(offset) * K1;
+        state[0] = state[0].rotate_right(29).wrapping_add(state[2]);
+        state[1] = state[1].wrapping_add(read_u64(offset).wrapping_mul(K1));
         offset += 8;
-        state[1] = state[1].rotate_right(29) + state[3];
-        state[2] += read_u64(offset) * K2;
+        state[1] = state[1].rotate_right(29).wrapping_add(state[3]);
+        state[2] = state[2].wrapping_add(read_u64(offset).wrapping_mul(K2));
         offset += 8;
-        state[2] = state[2].rotate_right(29) + state[0];
-        state[3] += read_u64(offset) * K3;
+        state[2] = state[2].rotate_right(29).wrapping_add(state[0]);
+        state[3] = state[3].wrapping_add(read_u64(offset).wrapping_mul(K3));
         offset += 8;
-        state[3] = state[3].rotate_right(29) + state[1];
+        state[3] = state[3].rotate_right(29).wrapping_add(state[1]);
     }
 
     // Copy the mixed state to the output.
@@ -1,12 +1,27 @@
 pub mod aes;
 pub mod aquahash;
+pub mod ascon;
+pub mod blake2b;
+pub mod blake3;
+pub mod chacha;
 pub mod cityhash128;
+pub mod clmul;
+pub mod crc32c;
+pub mod finalizers;
 pub mod fnv1a;
 pub mod goodhart;
+pub mod hasher;
+pub mod highwayhash;
+pub mod keccak;
 pub mod meowhash;
 pub mod metrohash128;
+pub mod mum;
 pub mod murmur3;
+pub mod salsa20;
+pub mod siphash;
 pub mod skein;
 pub mod spookyhash2;
 pub mod tenthash;
+pub mod wyhash;
 pub mod xxhash3;
+pub mod xxhash64;
@@ -0,0 +1,40 @@
+//! The "mum" folded multiply used by wyhash, rapidhash, and foldhash.
+
+pub const IN_SIZE_BYTES: usize = 128 / 8;
+pub const OUT_SIZE_BYTES: usize = 64 / 8;
+pub const DIGEST_SIZE_BYTES: usize = 64 / 8;
+
+/// A single folded multiply.
+pub const DEFAULT_ROUNDS: usize = 1;
+
+// wyhash's first two secret words, which the inputs are xored with as in
+// wyhash's `_wymix(A ^ secret[0], B ^ secret[1])`.  Without this, a zero in
+// either input would zero out the product.
+const SECRET: [u64; 2] = [0x2d358dccaa6c78a5, 0x8bb84b93962eacc9];
+
+/// The bare folded multiply: the two 64-bit input words (xored with secret
+/// words) are multiplied to a 128-bit product, and the high and low halves of
+/// that product are xored together.
+///
+/// `rounds` is the number of multiplies.  Each multiply after the first is on
+/// the full 128-bit product of the previous one, xored with the secret words
+/// again, and only the final product is folded.  Note that this chaining is
+/// made up for testing purposes: wyhash, rapidhash, and foldhash never
+/// multiply a product again like this, so only a single round corresponds to
+/// what they actually do.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    assert!(in_bytes.len() == IN_SIZE_BYTES);
+    assert!(out_bytes.len() == OUT_SIZE_BYTES);
+
+    let mut a = u64::from_le_bytes((&in_bytes[0..8]).try_into().unwrap());
+    let mut b = u64::from_le_bytes((&in_bytes[8..16]).try_into().unwrap());
+
+    for _ in 0..rounds {
+        let product = (a ^ SECRET[0]) as u128 * (b ^ SECRET[1]) as u128;
+        a = product as u64;
+        b = (product >> 64) as u64;
+    }
+
+    // Copy the folded product to the output.
+    out_bytes[0..8].copy_from_slice(&u64::to_le_bytes(a ^ b));
+}
@@ -2,49 +2,71 @@ pub const IN_SIZE_BYTES: usize = 128 / 8;
 pub const OUT_SIZE_BYTES: usize = 128 / 8;
 pub const DIGEST_SIZE_BYTES: usize = 128 / 8;
 
+pub const DEFAULT_ROUNDS: usize = 1;
+
+const SEED: u64 = 0xe9e58282f1c2287e;
+
 /// The Murmur3 accumulator.
-pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
-    assert!(in_bytes.len() == IN_SIZE_BYTES);
+///
+/// `rounds` is the number of blocks absorbed.  The input is one or more
+/// whole blocks, and all blocks past its end are zeros.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    let mut initial_state = [0u8; OUT_SIZE_BYTES];
+    initial_state[0..8].copy_from_slice(&u64::to_le_bytes(SEED));
+    initial_state[8..16].copy_from_slice(&u64::to_le_bytes(SEED));
+    mix_input_from_state(&initial_state, in_bytes, out_bytes, rounds);
+}
+
+/// `mix_input()`, but starting from `prior_state` rather than the seed, as if
+/// partway through a longer input.  The state has the same layout as the
+/// output: `h2` and then `h1`.
+pub fn mix_input_from_state(
+    prior_state: &[u8],
+    in_bytes: &[u8],
+    out_bytes: &mut [u8],
+    rounds: usize,
+) {
+    assert!(!in_bytes.is_empty() && in_bytes.len().is_multiple_of(IN_SIZE_BYTES));
     assert!(out_bytes.len() == OUT_SIZE_BYTES);
+    assert!(prior_state.len() == OUT_SIZE_BYTES);
 
     const C1: u64 = 0x87c37b91114253d5;
     const C2: u64 = 0x4cf5ad432745937f;
-    const SEED: u64 = 0xe9e58282f1c2287e;
 
-    let mut h1 = SEED;
-    let mut h2 = SEED;
-    let rounds = 1; // Set higher to play with how much diffusion you get per block.
+    let mut h2 = u64::from_le_bytes((&prior_state[0..8]).try_into().unwrap());
+    let mut h1 = u64::from_le_bytes((&prior_state[8..16]).try_into().unwrap());
     for i in 0..rounds {
-        let [mut k1, mut k2] = if i == 0 {
+        let offset = i * IN_SIZE_BYTES;
+        let [mut k1, mut k2] = if offset < in_bytes.len() {
             // Copy the input into the right layout.
             [
-                u64::from_le_bytes((&in_bytes[0..8]).try_into().unwrap()),
-                u64::from_le_bytes((&in_bytes[8..16]).try_into().unwrap()),
+                u64::from_le_bytes((&in_bytes[offset..(offset + 8)]).try_into().unwrap()),
+                u64::from_le_bytes((&in_bytes[(offset + 8)..(offset + 16)]).try_into().unwrap()),
             ]
         } else {
-            // We use zeros after the first round so that we're just
-            // tracking how well that first block diffuses.  Normally
+            // We use zeros past the end of the input so that we're just
+            // tracking how well the input blocks diffuse.  Normally
             // in Murmur3 additional blocks would be accumulated here.
             [0, 0]
         };
 
-        k1 *= C1;
+        k1 = k1.wrapping_mul(C1);
         k1 = k1.rotate_left(31);
-        k1 *= C2;
+        k1 = k1.wrapping_mul(C2);
         h1 ^= k1;
 
         h1 = h1.rotate_left(27);
-        h1 += h2;
-        h1 = h1 * 5 + 0x52dce729;
+        h1 = h1.wrapping_add(h2);
+        h1 = h1.wrapping_mul(5).wrapping_add(0x52dce729);
 
-        k2 *= C2;
+        k2 = k2.wrapping_mul(C2);
         k2 = k2.rotate_left(33);
-        k2 *= C1;
+        k2 = k2.wrapping_mul(C1);
         h2 ^= k2;
 
         h2 = h2.rotate_left(31);
-        h2 += h1;
-        h2 = h2 * 5 + 0x38495ab5;
+        h2 = h2.wrapping_add(h1);
+        h2 = h2.wrapping_mul(5).wrapping_add(0x38495ab5);
     }
 
     // Copy the mixed state to the output.
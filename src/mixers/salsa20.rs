@@ -0,0 +1,50 @@
+//! The Salsa20 permutation.
+
+pub const IN_SIZE_BYTES: usize = 512 / 8;
+pub const OUT_SIZE_BYTES: usize = 512 / 8;
+pub const DIGEST_SIZE_BYTES: usize = 512 / 8;
+
+/// The number of double rounds in Salsa20/20.  Salsa20/8 and Salsa20/12 use 4
+/// and 6.
+pub const DEFAULT_ROUNDS: usize = 10;
+
+/// The Salsa20 permutation, with `rounds` double rounds (a column round
+/// followed by a row round).
+///
+/// This is just the permutation, without Salsa20's feed-forward of the input
+/// state, since that doesn't contribute to mixing.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    assert!(in_bytes.len() == IN_SIZE_BYTES);
+    assert!(out_bytes.len() == OUT_SIZE_BYTES);
+
+    // Copy the state into the right layout.
+    let mut x: [u32; 16] = std::array::from_fn(|i| {
+        u32::from_le_bytes((&in_bytes[(i * 4)..((i + 1) * 4)]).try_into().unwrap())
+    });
+
+    for _ in 0..rounds {
+        // Column round.
+        quarter_round(&mut x, 0, 4, 8, 12);
+        quarter_round(&mut x, 5, 9, 13, 1);
+        quarter_round(&mut x, 10, 14, 2, 6);
+        quarter_round(&mut x, 15, 3, 7, 11);
+
+        // Row round.
+        quarter_round(&mut x, 0, 1, 2, 3);
+        quarter_round(&mut x, 5, 6, 7, 4);
+        quarter_round(&mut x, 10, 11, 8, 9);
+        quarter_round(&mut x, 15, 12, 13, 14);
+    }
+
+    // Copy the mixed state to the output.
+    for (i, word) in x.iter().enumerate() {
+        out_bytes[(i * 4)..((i + 1) * 4)].copy_from_slice(&u32::to_le_bytes(*word));
+    }
+}
+
+fn quarter_round(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+    x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+    x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+    x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+}
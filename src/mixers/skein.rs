@@ -1,16 +1,47 @@
-//! Skein's 256-bit mixing function, at only 7 rounds.
+//! Skein-256's block cipher, Threefish-256.
 //!
-//! NOTE: this is not reflective of Skein's quality.  Actual Skein uses a much
-//! larger number of rounds, which fully diffuses the hash state many times
-//! over.  This reduced-round version is included here purely for comparison
-//! with TentHash, which uses an effectively identical construction, but with
-//! constants optimized specifically for fewer rounds.
+//! `mix_input()` is just Threefish's MIX and permute rounds, by default at only
+//! 7 rounds.  NOTE: this is not reflective of Skein's quality.  Actual Skein
+//! uses a much larger number of rounds, which fully diffuses the hash state
+//! many times over, along with subkey injection.  This reduced-round version is
+//! included here purely for comparison with TentHash, which uses an
+//! effectively identical construction, but with constants optimized
+//! specifically for fewer rounds.
+//!
+//! `mix_input_threefish()` is the full Threefish-256 cipher, including the key
+//! schedule.
 
 pub const IN_SIZE_BYTES: usize = 256 / 8;
 pub const OUT_SIZE_BYTES: usize = 256 / 8;
 pub const DIGEST_SIZE_BYTES: usize = 256 / 8;
 
-pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
+/// Matches TentHash's round count.  The rotation constants repeat every 8
+/// rounds.
+pub const DEFAULT_ROUNDS: usize = 7;
+
+/// The number of rounds in Threefish-256.
+pub const THREEFISH_DEFAULT_ROUNDS: usize = 72;
+
+/// Skein-256-256's initial chaining value.
+pub const SKEIN_256_256_IV: [u64; 4] = [
+    0xfc9da860d048b449,
+    0x2fca66479fa7d833,
+    0xb33bc3896656840f,
+    0x6a54e920fde8da69,
+];
+
+const ROTATIONS: &[[u32; 2]] = &[
+    [14, 16],
+    [52, 57],
+    [23, 40],
+    [5, 37],
+    [25, 33],
+    [46, 12],
+    [58, 22],
+    [32, 32],
+];
+
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
     assert!(in_bytes.len() == IN_SIZE_BYTES);
     assert!(out_bytes.len() == OUT_SIZE_BYTES);
 
@@ -22,7 +53,7 @@ pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
         u64::from_le_bytes((&in_bytes[24..32]).try_into().unwrap()),
     ];
 
-    mix_state(&mut state);
+    mix_state(&mut state, rounds);
 
     // Copy the mixed state to the output.
     out_bytes[0..8].copy_from_slice(&u64::to_le_bytes(state[0]));
@@ -31,24 +62,81 @@ pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
     out_bytes[24..32].copy_from_slice(&u64::to_le_bytes(state[3]));
 }
 
-fn mix_state(state: &mut [u64; 4]) {
-    const ROTATIONS: &[[u32; 2]] = &[
-        [14, 16],
-        [52, 57],
-        [23, 40],
-        [5, 37],
-        [25, 33],
-        [46, 12],
-        [58, 22],
-        [32, 32],
+fn mix_state(state: &mut [u64; 4], rounds: usize) {
+    for rot_pair in ROTATIONS.iter().cycle().take(rounds) {
+        round(state, rot_pair);
+    }
+}
+
+/// Threefish-256 encrypting a single Skein-256-256 message block.
+///
+/// The input is the message block, which is encrypted with Skein-256-256's
+/// initial chaining value as the key and the tweak for a single-block
+/// 32-byte message.  `rounds` is the number of rounds, with a subkey injected
+/// before every fourth round and after the last one.
+///
+/// This leaves out Skein's feed-forward of the message block, since that
+/// doesn't contribute to mixing.
+pub fn mix_input_threefish(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    assert!(in_bytes.len() == IN_SIZE_BYTES);
+    assert!(out_bytes.len() == OUT_SIZE_BYTES);
+
+    // Position 32, first and final block, message type.
+    const TWEAK: [u64; 2] = [IN_SIZE_BYTES as u64, (1 << 62) | (1 << 63) | (48 << 56)];
+
+    // Copy the state into the right layout.
+    let mut state = [
+        u64::from_le_bytes((&in_bytes[0..8]).try_into().unwrap()),
+        u64::from_le_bytes((&in_bytes[8..16]).try_into().unwrap()),
+        u64::from_le_bytes((&in_bytes[16..24]).try_into().unwrap()),
+        u64::from_le_bytes((&in_bytes[24..32]).try_into().unwrap()),
     ];
 
-    for rot_pair in ROTATIONS.iter().take(7) {
-        state[0] = state[0].wrapping_add(state[1]);
-        state[1] = state[1].rotate_left(rot_pair[0]) ^ state[0];
-        state[2] = state[2].wrapping_add(state[3]);
-        state[3] = state[3].rotate_left(rot_pair[1]) ^ state[2];
+    threefish_encrypt(&SKEIN_256_256_IV, &TWEAK, &mut state, rounds);
 
-        state.swap(1, 3);
+    // Copy the mixed state to the output.
+    out_bytes[0..8].copy_from_slice(&u64::to_le_bytes(state[0]));
+    out_bytes[8..16].copy_from_slice(&u64::to_le_bytes(state[1]));
+    out_bytes[16..24].copy_from_slice(&u64::to_le_bytes(state[2]));
+    out_bytes[24..32].copy_from_slice(&u64::to_le_bytes(state[3]));
+}
+
+/// Threefish-256 encryption of `block` in place, with `rounds` rounds.
+pub fn threefish_encrypt(key: &[u64; 4], tweak: &[u64; 2], block: &mut [u64; 4], rounds: usize) {
+    // The extended key and tweak words for the key schedule.
+    let k = [
+        key[0],
+        key[1],
+        key[2],
+        key[3],
+        0x1bd11bdaa9fc1a22 ^ key[0] ^ key[1] ^ key[2] ^ key[3],
+    ];
+    let t = [tweak[0], tweak[1], tweak[0] ^ tweak[1]];
+
+    let inject_subkey = |block: &mut [u64; 4], s: usize| {
+        block[0] = block[0].wrapping_add(k[s % 5]);
+        block[1] = block[1].wrapping_add(k[(s + 1) % 5]).wrapping_add(t[s % 3]);
+        block[2] = block[2]
+            .wrapping_add(k[(s + 2) % 5])
+            .wrapping_add(t[(s + 1) % 3]);
+        block[3] = block[3].wrapping_add(k[(s + 3) % 5]).wrapping_add(s as u64);
+    };
+
+    for (d, rot_pair) in ROTATIONS.iter().cycle().take(rounds).enumerate() {
+        if d % 4 == 0 {
+            inject_subkey(block, d / 4);
+        }
+        round(block, rot_pair);
     }
+    inject_subkey(block, rounds.div_ceil(4));
+}
+
+/// One round: two MIXes, and then the word permutation.
+fn round(state: &mut [u64; 4], rot_pair: &[u32; 2]) {
+    state[0] = state[0].wrapping_add(state[1]);
+    state[1] = state[1].rotate_left(rot_pair[0]) ^ state[0];
+    state[2] = state[2].wrapping_add(state[3]);
+    state[3] = state[3].rotate_left(rot_pair[1]) ^ state[2];
+
+    state.swap(1, 3);
 }
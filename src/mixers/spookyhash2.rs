@@ -2,17 +2,35 @@ pub const IN_SIZE_BYTES: usize = 768 / 8;
 pub const OUT_SIZE_BYTES: usize = 768 / 8;
 pub const DIGEST_SIZE_BYTES: usize = 128 / 8;
 
-// The absorber from SpookyHash 2.
-pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
-    assert!(in_bytes.len() == IN_SIZE_BYTES);
+pub const DEFAULT_ROUNDS: usize = 1;
+
+/// The absorber from SpookyHash 2.
+///
+/// `rounds` is the number of blocks absorbed.  The input is one or more whole
+/// blocks, and all blocks past its end are zeros.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    mix_input_from_state(&[0; OUT_SIZE_BYTES], in_bytes, out_bytes, rounds);
+}
+
+/// `mix_input()`, but starting from `prior_state` rather than zeros, as if
+/// partway through a longer input.  The state has the same layout as the
+/// output.
+pub fn mix_input_from_state(
+    prior_state: &[u8],
+    in_bytes: &[u8],
+    out_bytes: &mut [u8],
+    rounds: usize,
+) {
+    assert!(!in_bytes.is_empty() && in_bytes.len().is_multiple_of(IN_SIZE_BYTES));
     assert!(out_bytes.len() == OUT_SIZE_BYTES);
+    assert!(prior_state.len() == OUT_SIZE_BYTES);
 
     // Fetches the 64-bit chunk of input data at byte offset `i`. Returns 0 if
     // it's out-of-bounds, which serves to pretend like there is an infinite
     // stream of zeroed out bytes after the initial data.  This is useful for
     // testing additional rounds without interference from other data.
     let fetch64 = |i: usize| -> u64 {
-        if (i + 8) <= IN_SIZE_BYTES {
+        if (i + 8) <= in_bytes.len() {
             u64::from_le_bytes((&in_bytes[i..(i + 8)]).try_into().unwrap())
         } else {
             0
@@ -20,25 +38,13 @@ pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
     };
 
     let mut data_offset = 0;
-    let mut state = [0u64; 12];
-
-    let rounds = 1;
+    let mut state: [u64; 12] = std::array::from_fn(|i| {
+        u64::from_le_bytes((&prior_state[(i * 8)..((i + 1) * 8)]).try_into().unwrap())
+    });
 
-    #[cfg_attr(rustfmt, rustfmt_skip)]
     for _ in 0..rounds {
-        state[0]  += fetch64(data_offset + (8 * 0));  state[2]  ^= state[10]; state[11] ^= state[0];  state[0]  = state[0].rotate_left(11);  state[11] += state[1];
-        state[1]  += fetch64(data_offset + (8 * 1));  state[3]  ^= state[11]; state[0]  ^= state[1];  state[1]  = state[1].rotate_left(32);  state[0]  += state[2];
-        state[2]  += fetch64(data_offset + (8 * 2));  state[4]  ^= state[0];  state[1]  ^= state[2];  state[2]  = state[2].rotate_left(43);  state[1]  += state[3];
-        state[3]  += fetch64(data_offset + (8 * 3));  state[5]  ^= state[1];  state[2]  ^= state[3];  state[3]  = state[3].rotate_left(31);  state[2]  += state[4];
-        state[4]  += fetch64(data_offset + (8 * 4));  state[6]  ^= state[2];  state[3]  ^= state[4];  state[4]  = state[4].rotate_left(17);  state[3]  += state[5];
-        state[5]  += fetch64(data_offset + (8 * 5));  state[7]  ^= state[3];  state[4]  ^= state[5];  state[5]  = state[5].rotate_left(28);  state[4]  += state[6];
-        state[6]  += fetch64(data_offset + (8 * 6));  state[8]  ^= state[4];  state[5]  ^= state[6];  state[6]  = state[6].rotate_left(39);  state[5]  += state[7];
-        state[7]  += fetch64(data_offset + (8 * 7));  state[9]  ^= state[5];  state[6]  ^= state[7];  state[7]  = state[7].rotate_left(57);  state[6]  += state[8];
-        state[8]  += fetch64(data_offset + (8 * 8));  state[10] ^= state[6];  state[7]  ^= state[8];  state[8]  = state[8].rotate_left(55);  state[7]  += state[9];
-        state[9]  += fetch64(data_offset + (8 * 9));  state[11] ^= state[7];  state[8]  ^= state[9];  state[9]  = state[9].rotate_left(54);  state[8]  += state[10];
-        state[10] += fetch64(data_offset + (8 * 10)); state[0]  ^= state[8];  state[9]  ^= state[10]; state[10] = state[10].rotate_left(22); state[9]  += state[11];
-        state[11] += fetch64(data_offset + (8 * 11)); state[1]  ^= state[9];  state[10] ^= state[11]; state[11] = state[11].rotate_left(46); state[10] += state[0];
-
+        let data = std::array::from_fn(|i| fetch64(data_offset + (8 * i)));
+        mix(&mut state, &data);
         data_offset += IN_SIZE_BYTES;
     }
 
@@ -56,3 +62,20 @@ pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
     out_bytes[80..88].copy_from_slice(&u64::to_le_bytes(state[10]));
     out_bytes[88..96].copy_from_slice(&u64::to_le_bytes(state[11]));
 }
+
+/// SpookyHash's `Mix()`, absorbing one block of 12 words.
+#[rustfmt::skip]
+fn mix(state: &mut [u64; 12], data: &[u64; 12]) {
+    state[0]  = state[0].wrapping_add(data[0]);   state[2]  ^= state[10]; state[11] ^= state[0];  state[0]  = state[0].rotate_left(11);  state[11] = state[11].wrapping_add(state[1]);
+    state[1]  = state[1].wrapping_add(data[1]);   state[3]  ^= state[11]; state[0]  ^= state[1];  state[1]  = state[1].rotate_left(32);  state[0]  = state[0].wrapping_add(state[2]);
+    state[2]  = state[2].wrapping_add(data[2]);   state[4]  ^= state[0];  state[1]  ^= state[2];  state[2]  = state[2].rotate_left(43);  state[1]  = state[1].wrapping_add(state[3]);
+    state[3]  = state[3].wrapping_add(data[3]);   state[5]  ^= state[1];  state[2]  ^= state[3];  state[3]  = state[3].rotate_left(31);  state[2]  = state[2].wrapping_add(state[4]);
+    state[4]  = state[4].wrapping_add(data[4]);   state[6]  ^= state[2];  state[3]  ^= state[4];  state[4]  = state[4].rotate_left(17);  state[3]  = state[3].wrapping_add(state[5]);
+    state[5]  = state[5].wrapping_add(data[5]);   state[7]  ^= state[3];  state[4]  ^= state[5];  state[5]  = state[5].rotate_left(28);  state[4]  = state[4].wrapping_add(state[6]);
+    state[6]  = state[6].wrapping_add(data[6]);   state[8]  ^= state[4];  state[5]  ^= state[6];  state[6]  = state[6].rotate_left(39);  state[5]  = state[5].wrapping_add(state[7]);
+    state[7]  = state[7].wrapping_add(data[7]);   state[9]  ^= state[5];  state[6]  ^= state[7];  state[7]  = state[7].rotate_left(57);  state[6]  = state[6].wrapping_add(state[8]);
+    state[8]  = state[8].wrapping_add(data[8]);   state[10] ^= state[6];  state[7]  ^= state[8];  state[8]  = state[8].rotate_left(55);  state[7]  = state[7].wrapping_add(state[9]);
+    state[9]  = state[9].wrapping_add(data[9]);   state[11] ^= state[7];  state[8]  ^= state[9];  state[9]  = state[9].rotate_left(54);  state[8]  = state[8].wrapping_add(state[10]);
+    state[10] = state[10].wrapping_add(data[10]); state[0]  ^= state[8];  state[9]  ^= state[10]; state[10] = state[10].rotate_left(22); state[9]  = state[9].wrapping_add(state[11]);
+    state[11] = state[11].wrapping_add(data[11]); state[1]  ^= state[9];  state[10] ^= state[11]; state[11] = state[11].rotate_left(46); state[10] = state[10].wrapping_add(state[0]);
+}
@@ -4,7 +4,11 @@ pub const IN_SIZE_BYTES: usize = 256 / 8;
 pub const OUT_SIZE_BYTES: usize = 256 / 8;
 pub const DIGEST_SIZE_BYTES: usize = 160 / 8;
 
-pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
+/// The number of rounds TentHash actually uses.  The rotation constants
+/// repeat after that.
+pub const DEFAULT_ROUNDS: usize = 7;
+
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
     assert!(in_bytes.len() == IN_SIZE_BYTES);
     assert!(out_bytes.len() == OUT_SIZE_BYTES);
 
@@ -16,7 +20,7 @@ pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
         u64::from_le_bytes((&in_bytes[24..32]).try_into().unwrap()),
     ];
 
-    mix_state(&mut state);
+    mix_state(&mut state, rounds);
 
     // Copy the mixed state to the output.
     out_bytes[0..8].copy_from_slice(&u64::to_le_bytes(state[0]));
@@ -25,7 +29,7 @@ pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
     out_bytes[24..32].copy_from_slice(&u64::to_le_bytes(state[3]));
 }
 
-fn mix_state(state: &mut [u64; 4]) {
+fn mix_state(state: &mut [u64; 4], rounds: usize) {
     const ROTATIONS: &[[u32; 2]] = &[
         [16, 28],
         [14, 57],
@@ -36,7 +40,7 @@ fn mix_state(state: &mut [u64; 4]) {
         [44, 13],
     ];
 
-    for rot_pair in ROTATIONS.iter() {
+    for rot_pair in ROTATIONS.iter().cycle().take(rounds) {
         state[0] = state[0].wrapping_add(state[2]);
         state[1] = state[1].wrapping_add(state[3]);
         state[2] = state[2].rotate_left(rot_pair[0]) ^ state[0];
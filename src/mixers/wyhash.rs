@@ -0,0 +1,72 @@
+//! The wyhash/rapidhash block absorber.
+
+pub const IN_SIZE_BYTES: usize = 384 / 8;
+pub const OUT_SIZE_BYTES: usize = 192 / 8;
+pub const DIGEST_SIZE_BYTES: usize = 64 / 8;
+
+pub const DEFAULT_ROUNDS: usize = 1;
+
+const SECRET: [u64; 4] = [
+    0x2d358dccaa6c78a5,
+    0x8bb84b93962eacc9,
+    0x4b33a62ed433d4a3,
+    0x4d5a2da51de1aa47,
+];
+
+/// The wyhash (final version 4.2) accumulator for inputs over 48 bytes.
+///
+/// Each 48-byte block is split across three independent lanes, each of which
+/// absorbs 16 bytes with a folded multiply.  rapidhash's accumulator is the
+/// same, except that it xors its lanes with `secret[0..3]` rather than
+/// `secret[1..4]`, and incorporates the input length into the initial state.
+///
+/// `rounds` is the number of blocks absorbed.  The input is one or more
+/// whole blocks, and all blocks past its end are zeros.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    assert!(!in_bytes.is_empty() && in_bytes.len().is_multiple_of(IN_SIZE_BYTES));
+    assert!(out_bytes.len() == OUT_SIZE_BYTES);
+
+    // Fetches the 64-bit chunk of input data at byte offset `i`. Returns 0 if
+    // it's out-of-bounds, which serves to pretend like there is an infinite
+    // stream of zeroed out bytes after the initial data.  This is useful for
+    // testing additional rounds without interference from other data.
+    let read_u64 = |i: usize| -> u64 {
+        if (i + 8) <= in_bytes.len() {
+            u64::from_le_bytes((&in_bytes[i..(i + 8)]).try_into().unwrap())
+        } else {
+            0
+        }
+    };
+
+    // Initial state, with a seed of zero.
+    let mut seed = 0u64;
+    seed ^= mix(seed ^ SECRET[0], SECRET[1]);
+    let mut see1 = seed;
+    let mut see2 = seed;
+
+    let mut offset = 0;
+    for _ in 0..rounds {
+        seed = mix(read_u64(offset) ^ SECRET[1], read_u64(offset + 8) ^ seed);
+        see1 = mix(
+            read_u64(offset + 16) ^ SECRET[2],
+            read_u64(offset + 24) ^ see1,
+        );
+        see2 = mix(
+            read_u64(offset + 32) ^ SECRET[3],
+            read_u64(offset + 40) ^ see2,
+        );
+        offset += 48;
+    }
+
+    // Copy the mixed state to the output.
+    out_bytes[0..8].copy_from_slice(&u64::to_le_bytes(seed));
+    out_bytes[8..16].copy_from_slice(&u64::to_le_bytes(see1));
+    out_bytes[16..24].copy_from_slice(&u64::to_le_bytes(see2));
+}
+
+/// wyhash's `_wymix()`: a 64x64->128 multiply, folded to 64 bits by xoring
+/// the high and low halves.
+fn mix(a: u64, b: u64) -> u64 {
+    let product = a as u128 * b as u128;
+    product as u64 ^ (product >> 64) as u64
+}
@@ -2,6 +2,8 @@ pub const IN_SIZE_BYTES: usize = 512 / 8;
 pub const OUT_SIZE_BYTES: usize = 512 / 8;
 pub const DIGEST_SIZE_BYTES: usize = 128 / 8;
 
+pub const DEFAULT_ROUNDS: usize = 1;
+
 const PRIME32_1: u32 = 0x9E3779B1;
 const PRIME32_2: u32 = 0x85EBCA77;
 const PRIME32_3: u32 = 0xC2B2AE3D;
@@ -14,32 +16,33 @@ const PRIME64_4: u64 = 0x85EBCA77C2B2AE63;
 const PRIME64_5: u64 = 0x27D4EB2F165667C5;
 
 // Unlike the standard implementation, we store the secrets as u64s rather than
-// as a byte array, since that's how they're used anyway.
+// as a byte array, since that's how they're used anyway.  These are the
+// little-endian reads of the standard byte array.
 const SECRET: [u64; 24] = [
-    0xb8fe6c3923a44bbe,
-    0x7c01812cf721ad1c,
-    0xded46de9839097db,
-    0x7240a4a4b7b3671f,
-    0xcb79e64eccc0e578,
-    0x825ad07dccff7221,
-    0xb8084674f743248e,
-    0xe03590e6813a264c,
-    0x3c2852bb91c300cb,
-    0x88d0658b1b532ea3,
-    0x71644897a20df94e,
-    0x3819ef46a9deacd8,
-    0xa8fa763fe39c343f,
-    0xf9dcbbc7c70b4f1d,
-    0x8a51e04bcdb45931,
-    0xc89f7ec9d9787364,
-    0xeac5ac8334d3ebc3,
-    0xc581a0fffa1363eb,
-    0x170ddd51b7f0da49,
-    0xd316552629d4689e,
-    0x2b16be587d47a1fc,
-    0x8ff8b8d17ad031ce,
-    0x45cb3a8f95160428,
-    0xafd7fbcabb4b407e,
+    0xbe4ba423396cfeb8,
+    0x1cad21f72c81017c,
+    0xdb979083e96dd4de,
+    0x1f67b3b7a4a44072,
+    0x78e5c0cc4ee679cb,
+    0x2172ffcc7dd05a82,
+    0x8e2443f7744608b8,
+    0x4c263a81e69035e0,
+    0xcb00c391bb52283c,
+    0xa32e531b8b65d088,
+    0x4ef90da297486471,
+    0xd8acdea946ef1938,
+    0x3f349ce33f76faa8,
+    0x1d4f0bc7c7bbdcf9,
+    0x3159b4cd4be0518a,
+    0x647378d9c97e9fc8,
+    0xc3ebd33483acc5ea,
+    0xeb6313faffa081c5,
+    0x49daf0b751dd0d17,
+    0x9e68d429265516d3,
+    0xfca1477d58be162b,
+    0xce31d07ad1b8f88f,
+    0x280416958f3acb45,
+    0x7e404bbbcafbd7af,
 ];
 
 /// The large-size xxhash3 accumulator, which runs in the inner
@@ -55,37 +58,86 @@ const SECRET: [u64; 24] = [
 ///
 /// We use xxhash3's terminology in the code below for consistency
 /// with its specification.
-pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
-    assert!(in_bytes.len() == IN_SIZE_BYTES);
+///
+/// `rounds` is the number of stripes accumulated.  The input is one or more
+/// whole stripes, and all stripes past its end are zeros.  This leaves out
+/// the scramble step that xxhash3 does at the end of every block of 16
+/// stripes.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    accumulate(in_bytes, out_bytes, rounds, false, None);
+}
+
+/// `mix_input()`, but starting from `prior_state` rather than the default
+/// accumulators, as if partway through a longer input at the start of a
+/// block.  The state has the same layout as the output.
+pub fn mix_input_from_state(
+    prior_state: &[u8],
+    in_bytes: &[u8],
+    out_bytes: &mut [u8],
+    rounds: usize,
+) {
+    accumulate(in_bytes, out_bytes, rounds, false, Some(prior_state));
+}
+
+/// The number of stripes in one full block.
+pub const STRIPES_PER_BLOCK: usize = 16;
+
+/// The xxhash3 accumulator including the scramble step, so that a full block
+/// is one complete iteration of xxhash3's outer loop.
+///
+/// `rounds` is the number of stripes accumulated, with the input treated the
+/// same as by `mix_input()`.  The accumulators are scrambled after every
+/// `STRIPES_PER_BLOCK` stripes.
+pub fn mix_input_scrambled(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    accumulate(in_bytes, out_bytes, rounds, true, None);
+}
+
+fn accumulate(
+    in_bytes: &[u8],
+    out_bytes: &mut [u8],
+    rounds: usize,
+    scramble: bool,
+    prior_state: Option<&[u8]>,
+) {
+    assert!(!in_bytes.is_empty() && in_bytes.len().is_multiple_of(IN_SIZE_BYTES));
     assert!(out_bytes.len() == OUT_SIZE_BYTES);
 
-    // Copy input into the right layout.
-    let stripe = [
-        u64::from_le_bytes((&in_bytes[0..8]).try_into().unwrap()),
-        u64::from_le_bytes((&in_bytes[8..16]).try_into().unwrap()),
-        u64::from_le_bytes((&in_bytes[16..24]).try_into().unwrap()),
-        u64::from_le_bytes((&in_bytes[24..32]).try_into().unwrap()),
-        u64::from_le_bytes((&in_bytes[32..40]).try_into().unwrap()),
-        u64::from_le_bytes((&in_bytes[40..48]).try_into().unwrap()),
-        u64::from_le_bytes((&in_bytes[48..56]).try_into().unwrap()),
-        u64::from_le_bytes((&in_bytes[56..64]).try_into().unwrap()),
-    ];
+    // Fetches the 64-bit chunk of input data at byte offset `i`. Returns 0 if
+    // it's out-of-bounds, which serves to pretend like there is an infinite
+    // stream of zeroed out bytes after the input data.  This is useful for
+    // testing additional rounds without interference from other data.
+    let fetch64 = |i: usize| -> u64 {
+        if (i + 8) <= in_bytes.len() {
+            u64::from_le_bytes((&in_bytes[i..(i + 8)]).try_into().unwrap())
+        } else {
+            0
+        }
+    };
 
-    // Set accumulator state to the default.
-    let mut accum_state = [
-        PRIME32_3 as u64,
-        PRIME64_1,
-        PRIME64_2,
-        PRIME64_3,
-        PRIME64_4,
-        PRIME32_2 as u64,
-        PRIME64_5,
-        PRIME32_1 as u64,
-    ];
+    // Set accumulator state to the default, or the prior state if given.
+    let mut accum_state = match prior_state {
+        Some(prior_state) => {
+            assert!(prior_state.len() == OUT_SIZE_BYTES);
+            std::array::from_fn(|i| {
+                u64::from_le_bytes((&prior_state[(i * 8)..((i + 1) * 8)]).try_into().unwrap())
+            })
+        }
+        None => [
+            PRIME32_3 as u64,
+            PRIME64_1,
+            PRIME64_2,
+            PRIME64_3,
+            PRIME64_4,
+            PRIME32_2 as u64,
+            PRIME64_5,
+            PRIME32_1 as u64,
+        ],
+    };
 
-    let rounds = 1; // Set higher to play with how much diffusion you get per block.
-    for i in 0..rounds {
-        let secret_offset = (i * 8) % SECRET.len();
+    for round in 0..rounds {
+        // Each stripe in a block advances 8 bytes (one u64) through the
+        // secret, and starts over with the next block.
+        let secret_offset = round % STRIPES_PER_BLOCK;
 
         // From the xxhash3 spec:
         // ```
@@ -100,10 +152,29 @@ pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8]) {
         // ```
         let secret_words = &SECRET[secret_offset..];
         for i in 0..8 {
-            let chunk = stripe.get(i).map(|c| *c).unwrap_or(0); // Assume off-the-end data is a stream of zeros, for rounds testing.
+            let chunk = fetch64(round * IN_SIZE_BYTES + i * 8);
             let value = chunk ^ secret_words[i];
-            accum_state[i ^ 1] += chunk;
-            accum_state[i] += (value & 0xffffffff) * (value >> 32);
+            accum_state[i ^ 1] = accum_state[i ^ 1].wrapping_add(chunk);
+            accum_state[i] = accum_state[i].wrapping_add((value & 0xffffffff) * (value >> 32));
+        }
+
+        // From the xxhash3 spec:
+        // ```
+        // scramble():
+        //   u64 secretWords[8] = secret[secretSize - 64:secretSize];
+        //   for (i = 0; i < 8; i++) {
+        //     acc[i] = acc[i] xor (acc[i] >> 47);
+        //     acc[i] = acc[i] xor secretWords[i];
+        //     acc[i] = acc[i] * PRIME32_1;
+        //   }
+        // ```
+        if scramble && (round + 1) % STRIPES_PER_BLOCK == 0 {
+            let secret_words = &SECRET[(SECRET.len() - 8)..];
+            for i in 0..8 {
+                accum_state[i] ^= accum_state[i] >> 47;
+                accum_state[i] ^= secret_words[i];
+                accum_state[i] = accum_state[i].wrapping_mul(PRIME32_1 as u64);
+            }
         }
     }
 
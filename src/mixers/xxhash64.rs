@@ -0,0 +1,57 @@
+pub const IN_SIZE_BYTES: usize = 256 / 8;
+pub const OUT_SIZE_BYTES: usize = 256 / 8;
+pub const DIGEST_SIZE_BYTES: usize = 64 / 8;
+
+pub const DEFAULT_ROUNDS: usize = 1;
+
+const PRIME64_1: u64 = 0x9E3779B185EBCA87;
+const PRIME64_2: u64 = 0xC2B2AE3D27D4EB4F;
+
+/// The xxHash64 accumulator: four independent lanes, each absorbing 8 bytes
+/// of every 32-byte stripe.
+///
+/// `rounds` is the number of stripes absorbed.  The input is one or more
+/// whole stripes, and all stripes past its end are zeros.
+pub fn mix_input(in_bytes: &[u8], out_bytes: &mut [u8], rounds: usize) {
+    assert!(!in_bytes.is_empty() && in_bytes.len().is_multiple_of(IN_SIZE_BYTES));
+    assert!(out_bytes.len() == OUT_SIZE_BYTES);
+
+    // Fetches the 64-bit chunk of input data at byte offset `i`. Returns 0 if
+    // it's out-of-bounds, which serves to pretend like there is an infinite
+    // stream of zeroed out bytes after the input data.  This is useful for
+    // testing additional rounds without interference from other data.
+    let fetch64 = |i: usize| -> u64 {
+        if (i + 8) <= in_bytes.len() {
+            u64::from_le_bytes((&in_bytes[i..(i + 8)]).try_into().unwrap())
+        } else {
+            0
+        }
+    };
+
+    // Initial state, with a seed of zero.
+    let mut lanes = [
+        PRIME64_1.wrapping_add(PRIME64_2),
+        PRIME64_2,
+        0,
+        0u64.wrapping_sub(PRIME64_1),
+    ];
+
+    for i in 0..rounds {
+        for (j, lane) in lanes.iter_mut().enumerate() {
+            *lane = round(*lane, fetch64(i * IN_SIZE_BYTES + j * 8));
+        }
+    }
+
+    // Copy the mixed state to the output.
+    out_bytes[0..8].copy_from_slice(&u64::to_le_bytes(lanes[0]));
+    out_bytes[8..16].copy_from_slice(&u64::to_le_bytes(lanes[1]));
+    out_bytes[16..24].copy_from_slice(&u64::to_le_bytes(lanes[2]));
+    out_bytes[24..32].copy_from_slice(&u64::to_le_bytes(lanes[3]));
+}
+
+/// xxHash64's `round()`.
+fn round(acc: u64, input: u64) -> u64 {
+    acc.wrapping_add(input.wrapping_mul(PRIME64_2))
+        .rotate_left(31)
+        .wrapping_mul(PRIME64_1)
+}
@@ -0,0 +1,204 @@
+//! The registry of input bit patterns that mixers are tested with.
+//!
+//! Each pattern is an input generator from `stats`, along with a default
+//! number of rounds to run it for and a short description for listings.
+
+use crate::stats::{
+    generate_bit_combinations, generate_bit_combinations_in, generate_counter, generate_counting,
+    generate_decimal, generate_gray_code, generate_hex_counter, generate_lowercase_words,
+    generate_n_random_bits, generate_random, generate_single_1_bit, generate_uuid_v4,
+    BitCombinations, CounterLayout, Endian,
+};
+
+pub struct Pattern<'a> {
+    pub name: &'a str,
+    pub gen_function: &'a dyn Fn(usize, &mut [u8]),
+
+    /// Number of rounds to run the pattern with. Zero is treated specially, and
+    /// means to use the bit width of the input.
+    pub rounds: usize,
+
+    pub description: &'a str,
+
+    /// Whether the pattern is run when none are chosen.  The more specialized
+    /// patterns are only run when asked for.
+    pub is_default: bool,
+}
+
+impl Pattern<'_> {
+    /// The number of rounds to run the pattern with for an input of
+    /// `input_size` bytes.
+    pub fn rounds_for(&self, input_size: usize) -> usize {
+        if self.rounds == 0 {
+            input_size * 8
+        } else {
+            self.rounds
+        }
+    }
+
+    /// Whether `name` refers to this pattern.  Names are matched ignoring
+    /// case, and with `-` and `_` standing in for spaces, so that they're
+    /// easy to pass on the command line.
+    pub fn is_named(&self, name: &str) -> bool {
+        normalize_name(self.name) == normalize_name(name)
+    }
+}
+
+/// All the registered patterns, in the order they're run.
+pub const PATTERNS: &[Pattern] = &[
+    Pattern {
+        name: "random",
+        gen_function: &generate_random,
+        rounds: 1 << 16,
+        description: "uniformly random inputs",
+        is_default: true,
+    },
+    Pattern {
+        name: "counting",
+        gen_function: &generate_counting,
+        rounds: 1 << 16,
+        description: "the round index as a little-endian integer in the lowest bytes",
+        is_default: true,
+    },
+    Pattern {
+        name: "big-endian counting",
+        gen_function: &|seed, bytes| {
+            let layout = CounterLayout {
+                endian: Endian::Big,
+                ..CounterLayout::default()
+            };
+            generate_counter(seed, bytes, &layout)
+        },
+        rounds: 1 << 16,
+        description: "the round index as a big-endian u64 in the first bytes, like database IDs",
+        is_default: false,
+    },
+    Pattern {
+        name: "high counting",
+        gen_function: &|seed, bytes| {
+            let layout = CounterLayout {
+                offset: bytes.len().saturating_sub(8),
+                ..CounterLayout::default()
+            };
+            generate_counter(seed, bytes, &layout)
+        },
+        rounds: 1 << 16,
+        description: "the round index as a little-endian u64 in the last bytes, the highest lanes",
+        is_default: false,
+    },
+    Pattern {
+        name: "pointers",
+        gen_function: &|seed, bytes| {
+            let layout = CounterLayout {
+                stride: 16,
+                start: 0x0000_7f3a_9c20_0000,
+                ..CounterLayout::default()
+            };
+            generate_counter(seed, bytes, &layout)
+        },
+        rounds: 1 << 16,
+        description: "16-byte aligned addresses counting up from a typical heap address",
+        is_default: false,
+    },
+    Pattern {
+        name: "gray code",
+        gen_function: &generate_gray_code,
+        rounds: 1 << 16,
+        description: "the round index's gray code in the lowest bytes",
+        is_default: true,
+    },
+    Pattern {
+        name: "bit combinations",
+        gen_function: &generate_bit_combinations,
+        rounds: 1 << 16,
+        description: "every input with zero bits set, then one bit, then two, and so on",
+        is_default: true,
+    },
+    Pattern {
+        name: "3-bit combinations",
+        gen_function: &|seed, bytes| {
+            generate_bit_combinations_in(seed, bytes, &BitCombinations::exact(3))
+        },
+        rounds: 1 << 16,
+        description: "every input with exactly 3 bits set",
+        is_default: false,
+    },
+    Pattern {
+        name: "complemented bit combinations",
+        gen_function: &|seed, bytes| {
+            let combinations = BitCombinations {
+                complement: true,
+                ..BitCombinations::default()
+            };
+            generate_bit_combinations_in(seed, bytes, &combinations)
+        },
+        rounds: 1 << 16,
+        description: "every input with zero bits cleared, then one bit, then two, and so on",
+        is_default: false,
+    },
+    Pattern {
+        name: "4 random bits",
+        gen_function: &|seed, bytes| generate_n_random_bits(seed, bytes, 4),
+        rounds: 1 << 16,
+        description: "zeros with 4 random bits set",
+        is_default: true,
+    },
+    Pattern {
+        name: "8 random bits",
+        gen_function: &|seed, bytes| generate_n_random_bits(seed, bytes, 8),
+        rounds: 1 << 16,
+        description: "zeros with 8 random bits set",
+        is_default: true,
+    },
+    Pattern {
+        name: "decimal",
+        gen_function: &generate_decimal,
+        rounds: 1 << 16,
+        description: "the round index as decimal text, e.g. \"1234\"",
+        is_default: false,
+    },
+    Pattern {
+        name: "hex counting",
+        gen_function: &generate_hex_counter,
+        rounds: 1 << 16,
+        description: "the round index as 16 hex digits of text",
+        is_default: false,
+    },
+    Pattern {
+        name: "words",
+        gen_function: &generate_lowercase_words,
+        rounds: 1 << 16,
+        description: "identifiers of random lowercase words from a small alphabet",
+        is_default: false,
+    },
+    Pattern {
+        name: "uuid text",
+        gen_function: &generate_uuid_v4,
+        rounds: 1 << 16,
+        description: "random version 4 UUIDs as 36 characters of text",
+        is_default: false,
+    },
+    Pattern {
+        name: "single-bit",
+        gen_function: &generate_single_1_bit,
+
+        // NOTE: because this test has a small, fixed number of rounds by its
+        // nature, the generated statistics should be interpreted a little
+        // differently. In particular, even a very good mixing function is
+        // unlikely to achieve "perfect" avalanche or BIC by this measure,
+        // purely because it's impossible to collect enough samples to reduce
+        // variance enough.
+        rounds: 0,
+        description: "zeros with each single bit set in turn, one round per input bit",
+        is_default: true,
+    },
+];
+
+/// Finds a registered pattern by name.  See `Pattern::is_named()`.
+pub fn find(name: &str) -> Option<&'static Pattern<'static>> {
+    PATTERNS.iter().find(|pattern| pattern.is_named(name))
+}
+
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase().replace(['-', '_'], " ")
+}
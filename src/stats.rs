@@ -1,7 +1,9 @@
-use std::{fs::File, path::Path};
+use std::{fs::File, ops::Range, path::Path};
 
 use nanorand::{Rng, WyRand};
 
+use crate::mixers::finalizers::stafford_mix13;
+
 pub struct Stats {
     pub input_bit_len: usize,
     pub output_bit_len: usize,
@@ -28,9 +30,9 @@ impl Stats {
         do_bic: bool,
     ) -> Self {
         Self {
-            input_bit_len: input_bit_len,
-            output_bit_len: output_bit_len,
-            digest_bit_len: digest_bit_len,
+            input_bit_len,
+            output_bit_len,
+            digest_bit_len,
             sample_count: 0,
             avalanche_chart: if do_avalanche {
                 vec![0; input_bit_len * output_bit_len]
@@ -305,6 +307,66 @@ impl Stats {
         n
     }
 
+    /// Returns the average and max bias of all in/out bit pairings between
+    /// the given input lane and output lane, where lanes are `lane_bits`
+    /// wide.  If the input or output isn't a multiple of the lane width, the
+    /// last lane is narrower.
+    ///
+    /// There must be at least one sample.
+    pub fn lane_bias(&self, in_lane: usize, out_lane: usize, lane_bits: usize) -> (f64, f64) {
+        assert!(self.sample_count > 0);
+        let norm = 1.0 / self.sample_count as f64;
+        let in_start = in_lane * lane_bits;
+        let in_end = (in_start + lane_bits).min(self.input_bit_len);
+        let out_start = out_lane * lane_bits;
+        let out_end = (out_start + lane_bits).min(self.output_bit_len);
+
+        let mut sum = 0.0;
+        let mut max = 0.0f64;
+        for in_bit in in_start..in_end {
+            for &flips in &self.get_row(in_bit)[out_start..out_end] {
+                let bias = p_to_bias(flips as f64 * norm);
+                sum += bias;
+                max = max.max(bias);
+            }
+        }
+        let count = (in_end - in_start) * (out_end - out_start);
+
+        (sum / count as f64, max)
+    }
+
+    /// Prints the avalanche chart aggregated into a lane-by-lane matrix, with
+    /// input lanes as rows and output lanes as columns.  Each cell is the
+    /// average and max bias between that pair of lanes, so lanes that never
+    /// influence each other show up as `1.00/1.00`.
+    pub fn print_lane_report(&self, lane_bits: usize) {
+        assert!(lane_bits > 0);
+        if self.avalanche_chart.is_empty() || self.sample_count == 0 {
+            return;
+        }
+
+        let in_lanes = self.input_bit_len.div_ceil(lane_bits);
+        let out_lanes = self.output_bit_len.div_ceil(lane_bits);
+
+        println!(
+            "    Lane bias, avg/max ({}-bit lanes, rows = input, columns = output):",
+            lane_bits
+        );
+        print!("        ");
+        for out_lane in 0..out_lanes {
+            print!(" {:>9}", out_lane);
+        }
+        println!();
+        for in_lane in 0..in_lanes {
+            print!("    {:>4}", in_lane);
+            for out_lane in 0..out_lanes {
+                let (avg, max) = self.lane_bias(in_lane, out_lane, lane_bits);
+                print!(" {:0.2}/{:0.2}", avg, max);
+            }
+            println!();
+        }
+    }
+
     pub fn print_report(&self) {
         if !self.avalanche_chart.is_empty() {
             println!(
@@ -401,6 +463,7 @@ impl Stats {
 ///   is a component of.  This is not actually used in any computations, and is
 ///   just provided as information in the final printouts.
 /// - `rounds`: how many test rounds to perform to produce the estimated chart.
+#[allow(clippy::too_many_arguments)]
 pub fn compute_stats<F1, F2>(
     generate_input: F1,
     mix: F2,
@@ -414,6 +477,85 @@ pub fn compute_stats<F1, F2>(
 where
     F1: Fn(usize, &mut [u8]),
     F2: Fn(&[u8], &mut [u8]),
+{
+    accumulate_stats(
+        generate_input,
+        |_, input: &[u8], output: &mut [u8]| mix(input, output),
+        input_size,
+        output_size,
+        digest_size,
+        rounds,
+        do_avalanche,
+        do_bic,
+    )
+}
+
+/// Like `compute_stats()`, but for mixers that can start from a prior state,
+/// such as the accumulators partway through a longer input.
+///
+/// - `mix`: function that takes a prior state, an input, and an output.
+/// - `state_size`: size of the prior state, in bytes.
+///
+/// Each round draws a random prior state, deterministically from the round's
+/// generator seed, and both the input and its bit-flipped variants are mixed
+/// from that same state.  The chart's input bits are the bits of the input,
+/// not the state.
+#[allow(clippy::too_many_arguments)]
+pub fn compute_stats_from_state<F1, F2>(
+    generate_input: F1,
+    mix: F2,
+    input_size: usize,
+    state_size: usize,
+    output_size: usize,
+    digest_size: usize,
+    rounds: usize,
+    do_avalanche: bool,
+    do_bic: bool,
+) -> Stats
+where
+    F1: Fn(usize, &mut [u8]),
+    F2: Fn(&[u8], &[u8], &mut [u8]),
+{
+    // The state for the current round, as (round, state).
+    let mut prior_state = (None, vec![0u8; state_size]);
+
+    accumulate_stats(
+        generate_input,
+        |round, input: &[u8], output: &mut [u8]| {
+            if prior_state.0 != Some(round) {
+                // Decorrelated from the seed, so that a random input pattern
+                // doesn't get an identical state.
+                let seed = mix64(round as u64 ^ 0x3a8f05c5b1e6d249) as usize;
+                generate_random(seed, &mut prior_state.1[..]);
+                prior_state.0 = Some(round);
+            }
+            mix(&prior_state.1[..], input, output)
+        },
+        input_size,
+        output_size,
+        digest_size,
+        rounds,
+        do_avalanche,
+        do_bic,
+    )
+}
+
+/// The shared implementation of `compute_stats()` and
+/// `compute_stats_from_state()`.  `mix` also takes the round.
+#[allow(clippy::too_many_arguments)]
+fn accumulate_stats<F1, F2>(
+    generate_input: F1,
+    mut mix: F2,
+    input_size: usize,
+    output_size: usize,
+    digest_size: usize,
+    rounds: usize,
+    do_avalanche: bool,
+    do_bic: bool,
+) -> Stats
+where
+    F1: Fn(usize, &mut [u8]),
+    F2: FnMut(usize, &[u8], &mut [u8]),
 {
     let mut chart = Stats::new(
         input_size * 8,
@@ -438,11 +580,11 @@ where
 
         generate_input(round, &mut input[..]);
 
-        mix(&input[..], &mut output[..]);
+        mix(round, &input[..], &mut output[..]);
         for in_bit_idx in 0..(input_size * 8) {
-            input_tweaked.copy_from_slice(&mut input[..]);
+            input_tweaked.copy_from_slice(&input[..]);
             input_tweaked[in_bit_idx / 8] ^= 1 << (in_bit_idx % 8);
-            mix(&input_tweaked[..], &mut output_tweaked[..]);
+            mix(round, &input_tweaked[..], &mut output_tweaked[..]);
 
             // Avalanche.
             if do_avalanche {
@@ -496,6 +638,142 @@ where
     chart
 }
 
+/// Computes an avalanche chart for a difference in one block of a multi-block
+/// message, as seen in an accumulator's state after absorbing the whole
+/// message.
+///
+/// - `absorb`: function that takes a message of one or more whole blocks,
+///   absorbs all of it, and outputs the resulting state.
+/// - `block_size`: size of a block, in bytes.
+/// - `state_size`: size of `absorb`'s output, in bytes.
+/// - `digest_size`: as for `compute_stats()`.
+/// - `diff_block`: the index of the block whose bits are flipped.
+/// - `following_blocks`: how many blocks are absorbed after that one.
+/// - `samples`: how many messages to produce the estimated chart from.
+///
+/// Unlike the accumulator mixers on their own, which absorb zeros after the
+/// input, every block of the message is random, fresh for each sample.  The
+/// chart's input bits are the bits of block `diff_block`.
+pub fn compute_chaining_stats<F>(
+    absorb: F,
+    block_size: usize,
+    state_size: usize,
+    digest_size: usize,
+    diff_block: usize,
+    following_blocks: usize,
+    samples: usize,
+) -> Stats
+where
+    F: Fn(&[u8], &mut [u8]),
+{
+    let mut chart = Stats::new(block_size * 8, state_size * 8, digest_size * 8, true, false);
+
+    let message_size = block_size * (diff_block + 1 + following_blocks);
+    let diff_offset = block_size * diff_block;
+
+    let mut message = vec![0u8; message_size];
+    let mut message_tweaked = vec![0u8; message_size];
+    let mut state = vec![0u8; state_size];
+    let mut state_tweaked = vec![0u8; state_size];
+
+    for sample in 0..samples {
+        generate_random(sample, &mut message[..]);
+        absorb(&message[..], &mut state[..]);
+
+        for in_bit_idx in 0..(block_size * 8) {
+            message_tweaked.copy_from_slice(&message[..]);
+            message_tweaked[diff_offset + in_bit_idx / 8] ^= 1 << (in_bit_idx % 8);
+            absorb(&message_tweaked[..], &mut state_tweaked[..]);
+
+            for out_bit_idx in 0..(state_size * 8) {
+                let i = out_bit_idx / 8;
+                let mask = 1 << (out_bit_idx % 8);
+                let flipped = (state[i] & mask) != (state_tweaked[i] & mask);
+
+                chart.accumulate(in_bit_idx, out_bit_idx, flipped);
+            }
+        }
+
+        chart.sample_count += 1;
+    }
+
+    chart
+}
+
+/// Measures how far `mix` is from being a polynomial of low degree over
+/// GF(2), which avalanche and BIC don't catch: an affine mixer like a CRC can
+/// flip every output bit half the time and still be trivially invertible.
+///
+/// Each sample draws a random input `x` and `order` random input differences,
+/// and takes the `order`-th derivative of `mix` at `x`: the xor of
+/// `mix(x ^ d)` over every `d` that is the xor of a subset of the
+/// differences.  If every output bit is a polynomial of degree below `order`
+/// in the input bits, the derivative is always zero.  So order 2 catches
+/// affine mixers, where `mix(x ^ a ^ b) == mix(x ^ a) ^ mix(x ^ b) ^ mix(x)`,
+/// and order 3 catches quadratic ones like a single carry-less multiply.
+///
+/// Returns the bias of each output bit of the derivative, in the same sense
+/// as the avalanche bias: 1.0 means the bit is constant (the degree is below
+/// `order`), and a good mixer gives biases near 0.0.
+///
+/// - `mix`: as for `compute_stats()`.
+/// - `input_size`: size of `mix`'s input, in bytes.
+/// - `output_size`: size of `mix`'s output, in bytes.
+/// - `order`: the order of the derivative, at least 1.
+/// - `samples`: how many derivatives to estimate the biases from.
+pub fn compute_derivative_bias<F>(
+    mix: F,
+    input_size: usize,
+    output_size: usize,
+    order: usize,
+    samples: usize,
+) -> Vec<f64>
+where
+    F: Fn(&[u8], &mut [u8]),
+{
+    assert!(order > 0 && samples > 0);
+
+    let mut base = vec![0u8; input_size];
+    let mut differences = vec![vec![0u8; input_size]; order];
+    let mut input = vec![0u8; input_size];
+    let mut output = vec![0u8; output_size];
+    let mut derivative = vec![0u8; output_size];
+    let mut ones = vec![0u32; output_size * 8];
+
+    for sample in 0..samples {
+        let seed = sample * (order + 1);
+        generate_random(seed, &mut base[..]);
+        for (i, difference) in differences.iter_mut().enumerate() {
+            generate_random(seed + 1 + i, &mut difference[..]);
+        }
+
+        derivative.fill(0);
+        for subset in 0..(1usize << order) {
+            input.copy_from_slice(&base[..]);
+            for (i, difference) in differences.iter().enumerate() {
+                if subset & (1 << i) != 0 {
+                    for (byte, &d) in input.iter_mut().zip(difference.iter()) {
+                        *byte ^= d;
+                    }
+                }
+            }
+            mix(&input[..], &mut output[..]);
+            for (byte, &o) in derivative.iter_mut().zip(output.iter()) {
+                *byte ^= o;
+            }
+        }
+
+        for (bit_idx, count) in ones.iter_mut().enumerate() {
+            *count += ((derivative[bit_idx / 8] >> (bit_idx % 8)) & 1) as u32;
+        }
+    }
+
+    let norm = 1.0 / samples as f64;
+    ones.iter()
+        .map(|&count| p_to_bias(count as f64 * norm))
+        .collect()
+}
+
 pub fn p_to_bias(p: f64) -> f64 {
     (p * 2.0 - 1.0).abs()
 }
@@ -553,85 +831,295 @@ pub fn generate_n_random_bits(seed: usize, bytes: &mut [u8], n: usize) {
 /// Generates a byte stream with the lowest bits simply counting up as an
 /// incrementing integer.
 pub fn generate_counting(index: usize, bytes: &mut [u8]) {
-    bytes[0..8].copy_from_slice(&u64::to_le_bytes(index as u64));
-    bytes[8..].fill(0);
+    generate_counter(index, bytes, &CounterLayout::default());
 }
 
 /// Generates a byte stream with the lowest bits simply iterating through gray
 /// codes in order.
 pub fn generate_gray_code(index: usize, bytes: &mut [u8]) {
-    let gray_code = index ^ (index >> 1);
-    bytes[0..8].copy_from_slice(&u64::to_le_bytes(gray_code as u64));
-    bytes[8..].fill(0);
+    generate_gray_code_counter(index, bytes, &CounterLayout::default());
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+/// Where and how a counter is written into the input, for
+/// `generate_counter()` and `generate_gray_code_counter()`.
+///
+/// The default is a little-endian u64 at the start of the input, counting up
+/// from zero by one, which is what `generate_counting()` uses.
+#[derive(Debug, Copy, Clone)]
+pub struct CounterLayout {
+    /// Byte offset of the counter in the input.
+    pub offset: usize,
+    /// Width of the counter in bytes, from 1 to 8.  The counter wraps at this
+    /// width.
+    pub width: usize,
+    pub endian: Endian,
+    /// How much the counter increases per index, e.g. 8 or 16 to mimic
+    /// pointers into an array.
+    pub stride: u64,
+    /// The counter's value at index zero.
+    pub start: u64,
+}
+
+impl Default for CounterLayout {
+    fn default() -> CounterLayout {
+        CounterLayout {
+            offset: 0,
+            width: 8,
+            endian: Endian::Little,
+            stride: 1,
+            start: 0,
+        }
+    }
+}
+
+impl CounterLayout {
+    /// The counter's value at `index`.
+    pub fn value(&self, index: usize) -> u64 {
+        self.start
+            .wrapping_add((index as u64).wrapping_mul(self.stride))
+    }
+
+    /// Zeros `bytes` and writes `value` into it.  If the counter runs past
+    /// the end of the input it's narrowed to fit, keeping its low bytes.
+    pub fn write(&self, value: u64, bytes: &mut [u8]) {
+        assert!((1..=8).contains(&self.width));
+
+        bytes.fill(0);
+        let width = self.width.min(bytes.len().saturating_sub(self.offset));
+        if width == 0 {
+            return;
+        }
+        let counter = &mut bytes[self.offset..(self.offset + width)];
+        match self.endian {
+            Endian::Little => counter.copy_from_slice(&value.to_le_bytes()[..width]),
+            Endian::Big => counter.copy_from_slice(&value.to_be_bytes()[(8 - width)..]),
+        }
+    }
+}
+
+/// Generates a byte stream of zeros with a counter in it, as laid out by
+/// `layout`.
+pub fn generate_counter(index: usize, bytes: &mut [u8], layout: &CounterLayout) {
+    layout.write(layout.value(index), bytes);
+}
+
+/// Like `generate_counter()`, but writing the gray code of the counter's
+/// value.
+pub fn generate_gray_code_counter(index: usize, bytes: &mut [u8], layout: &CounterLayout) {
+    let value = layout.value(index);
+    layout.write(value ^ (value >> 1), bytes);
 }
 
 /// Generates all combinations of setting zero bits, then one bit, then two
 /// bits, and so on, in that order.
-#[allow(dead_code)]
 pub fn generate_bit_combinations(index: usize, bytes: &mut [u8]) {
-    let bit_len = bytes.len() * 8;
+    generate_bit_combinations_in(index, bytes, &BitCombinations::default());
+}
 
-    // Compute the number of bits and the sub-index into that subsequence, for the
-    // given index.
-    let mut n = if bit_len < std::mem::size_of::<usize>() {
-        // Wrap to the total number of combinations if that's less than usize.
-        index % (1 << bit_len)
-    } else {
-        index
-    };
-    let mut bits = 0;
-    let mut combos = binomial(bit_len, bits);
-    while n >= combos {
-        n -= combos;
-        bits += 1;
-        combos = binomial(bit_len, bits);
+/// Which combinations `generate_bit_combinations_in()` enumerates.
+///
+/// The default is every combination of the whole input, which is what
+/// `generate_bit_combinations()` uses.
+#[derive(Debug, Clone)]
+pub struct BitCombinations {
+    /// The fewest bits set.
+    pub min_weight: usize,
+    /// The most bits set, inclusive.  Capped at the window's width.
+    pub max_weight: usize,
+    /// The bit positions that may be set, or `None` for the whole input.
+    /// Capped at the input's width.
+    pub window: Option<Range<usize>>,
+    /// Whether to invert the whole input, giving mostly-ones inputs.
+    pub complement: bool,
+}
+
+impl Default for BitCombinations {
+    fn default() -> BitCombinations {
+        BitCombinations {
+            min_weight: 0,
+            max_weight: usize::MAX,
+            window: None,
+            complement: false,
+        }
+    }
+}
+
+impl BitCombinations {
+    /// Every combination with exactly `weight` bits set.
+    pub fn exact(weight: usize) -> BitCombinations {
+        BitCombinations {
+            min_weight: weight,
+            max_weight: weight,
+            ..BitCombinations::default()
+        }
     }
 
-    // Generate the bits for the computed subsequence and sub-index.
+    /// The bit positions that may be set, for an input of `bit_len` bits.
+    pub fn window_for(&self, bit_len: usize) -> Range<usize> {
+        match &self.window {
+            Some(window) => window.start.min(bit_len)..window.end.min(bit_len),
+            None => 0..bit_len,
+        }
+    }
+
+    /// The number of combinations for an input of `bit_len` bits, or `None`
+    /// if that doesn't fit in a `usize`.
+    pub fn count(&self, bit_len: usize) -> Option<usize> {
+        let width = self.window_for(bit_len).len();
+        (self.min_weight..=self.max_weight.min(width))
+            .try_fold(0usize, |total, k| total.checked_add(binomial(width, k)?))
+    }
+}
+
+/// Generates the combinations described by `combinations`, in order of the
+/// number of bits set and then in colexicographic order, wrapping around if
+/// the index is past the last one.  If there are no such combinations, the
+/// input is all zeros (or all ones, when complemented).
+pub fn generate_bit_combinations_in(
+    index: usize,
+    bytes: &mut [u8],
+    combinations: &BitCombinations,
+) {
+    let window = combinations.window_for(bytes.len() * 8);
+    let max_weight = combinations.max_weight.min(window.len());
+
     bytes.fill(0);
-    let mut t = bit_len;
-    while t > 0 && bits > 0 {
-        let y = if t > bits { binomial(t - 1, bits) } else { 0 };
+    if combinations.min_weight <= max_weight {
+        // Compute the number of bits and the sub-index into that subsequence,
+        // for the given index.  Counts too large for a usize are larger than
+        // any index, so they never need wrapping.
+        let mut n = match combinations.count(bytes.len() * 8) {
+            Some(count) => index % count,
+            None => index,
+        };
+        let mut bits = combinations.min_weight;
+        let mut combos = binomial(window.len(), bits).unwrap_or(usize::MAX);
+        while n >= combos {
+            n -= combos;
+            bits += 1;
+            combos = binomial(window.len(), bits).unwrap_or(usize::MAX);
+        }
+
+        // Generate the bits for the computed subsequence and sub-index.
+        let mut t = window.len();
+        while t > 0 && bits > 0 {
+            let y = if t > bits {
+                binomial(t - 1, bits).unwrap_or(usize::MAX)
+            } else {
+                0
+            };
+
+            if n >= y {
+                let bit_idx = window.start + t - 1;
+                bytes[bit_idx / 8] |= 1 << (bit_idx % 8);
 
-        if n >= y {
-            let byte_idx = (t - 1) / 8;
-            let byte_mask = 1 << ((t - 1) % 8);
-            bytes[byte_idx] |= byte_mask;
+                n -= y;
+                bits -= 1;
+            }
 
-            n -= y;
-            bits -= 1;
+            t -= 1;
         }
+    }
 
-        t -= 1;
+    if combinations.complement {
+        for byte in bytes.iter_mut() {
+            *byte = !*byte;
+        }
     }
 }
 
-const fn binomial(n: usize, k: usize) -> usize {
+//-------------------------------------------------------------
+// Text generators.
+//
+// These mimic keys that are ASCII text, where the high bit of every byte is
+// zero and most of the other bits are heavily constrained.  The text starts
+// at the beginning of the input and is followed by zeros, and is truncated if
+// the input is too short.
+
+/// Generates the decimal rendering of the index, e.g. `"1234"`.
+pub fn generate_decimal(index: usize, bytes: &mut [u8]) {
+    write_text(index.to_string().as_bytes(), bytes);
+}
+
+/// Generates the index as 16 lowercase hex digits, e.g.
+/// `"00000000000004d2"`.
+pub fn generate_hex_counter(index: usize, bytes: &mut [u8]) {
+    write_text(format!("{:016x}", index).as_bytes(), bytes);
+}
+
+/// Generates an identifier of one to three random words joined by `_`, each
+/// of two to eight letters from a small alphabet, e.g. `"head_cab"`.
+pub fn generate_lowercase_words(seed: usize, bytes: &mut [u8]) {
+    const ALPHABET: &[u8] = b"abcdefgh";
+
+    let mut rng = WyRand::new_seed(mix64(seed as u64 ^ 0xd1b54a32d192ed03));
+    let mut text = Vec::new();
+    for word in 0..rng.generate_range(1..=3usize) {
+        if word > 0 {
+            text.push(b'_');
+        }
+        for _ in 0..rng.generate_range(2..=8usize) {
+            text.push(ALPHABET[rng.generate_range(0..ALPHABET.len())]);
+        }
+    }
+    write_text(&text, bytes);
+}
+
+/// Generates a random version 4 UUID in its usual lowercase text form, e.g.
+/// `"1b4e28ba-2fa1-41d2-883f-0016d3cca427"`.
+pub fn generate_uuid_v4(seed: usize, bytes: &mut [u8]) {
+    let mut rng = WyRand::new_seed(mix64(seed as u64 ^ 0x8cb92ba72f3d8dd7));
+    let mut uuid = [0u8; 16];
+    rng.fill_bytes(&mut uuid);
+    uuid[6] = (uuid[6] & 0x0f) | 0x40; // Version 4.
+    uuid[8] = (uuid[8] & 0x3f) | 0x80; // RFC 4122 variant.
+
+    let mut text = String::with_capacity(36);
+    for (i, byte) in uuid.iter().enumerate() {
+        if [4, 6, 8, 10].contains(&i) {
+            text.push('-');
+        }
+        text.push_str(&format!("{:02x}", byte));
+    }
+    write_text(text.as_bytes(), bytes);
+}
+
+/// Writes `text` at the start of `bytes`, truncated to fit, and zeros the
+/// rest.
+fn write_text(text: &[u8], bytes: &mut [u8]) {
+    let n = text.len().min(bytes.len());
+    bytes[..n].copy_from_slice(&text[..n]);
+    bytes[n..].fill(0);
+}
+
+//-------------------------------------------------------------
+
+/// The binomial coefficient, or `None` if it doesn't fit in a `usize`.
+fn binomial(n: usize, k: usize) -> Option<usize> {
     if k > n {
-        return 0;
+        return Some(0);
     }
 
-    if k == 0 {
-        1
-    } else if k > (n / 2) {
-        binomial(n, n - k)
-    } else {
-        n * binomial(n - 1, k - 1) / k
+    // Each step computes C(n, i + 1) = C(n, i) * (n - i) / (i + 1), which is
+    // always exact.
+    let k = k.min(n - k);
+    let mut c: usize = 1;
+    for i in 0..k {
+        let whole = (c / (i + 1)).checked_mul(n - i)?;
+        let part = (c % (i + 1)) * (n - i) / (i + 1);
+        c = whole.checked_add(part)?;
     }
+    Some(c)
 }
 
 /// 64-bit bijective bit mixer.
-fn mix64(mut n: u64) -> u64 {
+fn mix64(n: u64) -> u64 {
     // Break zero sensitivity.
-    n ^= 0x7be355f7c2e736d2;
-
-    // http://zimbry.blogspot.ch/2011/09/better-bit-mixing-improving-on.html
-    // (variant "Mix13")
-    n ^= n >> 30;
-    n = n.wrapping_mul(0xbf58476d1ce4e5b9);
-    n ^= n >> 27;
-    n = n.wrapping_mul(0x94d049bb133111eb);
-    n ^= n >> 31;
-
-    n
+    stafford_mix13(n ^ 0x7be355f7c2e736d2)
 }
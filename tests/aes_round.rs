@@ -0,0 +1,34 @@
+#![cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+
+use lib::aes_round::{aesdec_hw, aesdec_soft, aesenc_hw, aesenc_soft};
+use nanorand::{Rng, WyRand};
+
+const ITERATIONS: usize = 100_000;
+
+#[test]
+fn aesenc_soft_matches_hw() {
+    if !std::is_x86_feature_detected!("aes") {
+        return;
+    }
+
+    let mut rng = WyRand::new_seed(0x5aee66ffbc9d5f25);
+    for _ in 0..ITERATIONS {
+        let state = ((rng.generate::<u64>() as u128) << 64) | rng.generate::<u64>() as u128;
+        let key = ((rng.generate::<u64>() as u128) << 64) | rng.generate::<u64>() as u128;
+        assert_eq!(aesenc_soft(state, key), unsafe { aesenc_hw(state, key) });
+    }
+}
+
+#[test]
+fn aesdec_soft_matches_hw() {
+    if !std::is_x86_feature_detected!("aes") {
+        return;
+    }
+
+    let mut rng = WyRand::new_seed(0xb625245574d76546);
+    for _ in 0..ITERATIONS {
+        let state = ((rng.generate::<u64>() as u128) << 64) | rng.generate::<u64>() as u128;
+        let key = ((rng.generate::<u64>() as u128) << 64) | rng.generate::<u64>() as u128;
+        assert_eq!(aesdec_soft(state, key), unsafe { aesdec_hw(state, key) });
+    }
+}
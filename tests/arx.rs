@@ -0,0 +1,149 @@
+use lib::{
+    arx::Spec,
+    mixers::{goodhart, tenthash},
+};
+use nanorand::{Rng, WyRand};
+
+const ITERATIONS: usize = 1000;
+
+/// Checks that a spec has the same sizes and output as a built-in mixer, at
+/// every round count up to well past where the rotation constants repeat.
+fn check_against(
+    spec: &Spec,
+    mix_input: fn(&[u8], &mut [u8], usize),
+    size: usize,
+    digest_size: usize,
+    seed: u64,
+) {
+    assert_eq!(spec.size_bytes(), size);
+    assert_eq!(spec.digest_size_bytes(), digest_size);
+
+    let mut rng = WyRand::new_seed(seed);
+    let mut input = vec![0u8; size];
+    let mut expected = vec![0u8; size];
+    let mut actual = vec![0u8; size];
+    for _ in 0..ITERATIONS {
+        rng.fill_bytes(&mut input);
+        let rounds = rng.generate_range(0..40usize);
+        mix_input(&input, &mut expected, rounds);
+        spec.mix_input(&input, &mut actual, rounds);
+        assert_eq!(actual, expected, "{} rounds", rounds);
+    }
+}
+
+#[test]
+fn goodhart_spec() {
+    let spec = Spec::parse(include_str!("../specs/goodhart.arx")).unwrap();
+    assert_eq!(spec.default_rounds, goodhart::DEFAULT_ROUNDS);
+    check_against(
+        &spec,
+        goodhart::mix_input,
+        goodhart::IN_SIZE_BYTES,
+        goodhart::DIGEST_SIZE_BYTES,
+        0x6b2e6c8a1f47d359,
+    );
+}
+
+#[test]
+fn tenthash_spec() {
+    let spec = Spec::parse(include_str!("../specs/tenthash.arx")).unwrap();
+    assert_eq!(spec.default_rounds, tenthash::DEFAULT_ROUNDS);
+    check_against(
+        &spec,
+        tenthash::mix_input,
+        tenthash::IN_SIZE_BYTES,
+        tenthash::DIGEST_SIZE_BYTES,
+        0xd40f3e9b2a6c8175,
+    );
+}
+
+/// Narrow lanes, right rotation, multiplication by a constant, and repeat
+/// blocks, checked against the same operations on `u32`s.
+#[test]
+fn narrow_lanes_and_repeat() {
+    let spec = Spec::parse(
+        "lanes 2
+         width 32
+         round
+             repeat 2
+                 mul a 0x9e3779b9
+                 rotr b 7
+                 xor b a
+             end
+             swap a b",
+    )
+    .unwrap();
+    assert_eq!(spec.size_bytes(), 8);
+
+    let mut rng = WyRand::new_seed(0x1c93e0f5a7b2d846);
+    for _ in 0..ITERATIONS {
+        let input = rng.generate::<u64>().to_le_bytes();
+        let mut out = [0u8; 8];
+        spec.mix_input(&input, &mut out, 3);
+
+        let mut a = u32::from_le_bytes(input[0..4].try_into().unwrap());
+        let mut b = u32::from_le_bytes(input[4..8].try_into().unwrap());
+        for _ in 0..3 {
+            for _ in 0..2 {
+                a = a.wrapping_mul(0x9e3779b9);
+                b = b.rotate_right(7) ^ a;
+            }
+            std::mem::swap(&mut a, &mut b);
+        }
+        assert_eq!(out[0..4], a.to_le_bytes());
+        assert_eq!(out[4..8], b.to_le_bytes());
+    }
+}
+
+#[test]
+fn parse_errors() {
+    let error = |text| Spec::parse(text).unwrap_err();
+
+    assert_eq!(
+        error("lanes 2\nwidth 64\nround\nadd a c"),
+        "line 4: `c` is not a lane"
+    );
+    assert_eq!(
+        error("lanes 2\nwidth 64\nround\nfrob a"),
+        "line 4: unknown operation `frob`"
+    );
+    assert_eq!(
+        error("lanes 2\nwidth 63"),
+        "line 2: invalid `width` value 63"
+    );
+    assert_eq!(
+        error("lanes 2\nround"),
+        "line 2: `lanes` and `width` must come before any rounds"
+    );
+    assert_eq!(
+        error("lanes 2\nwidth 64\nround\nrepeat 2\nxor a b"),
+        "`repeat` block not closed with `end`"
+    );
+    assert_eq!(error("lanes 2\nwidth 64"), "no rounds");
+}
+
+#[test]
+fn rotations() {
+    let mut spec = Spec::parse(include_str!("../specs/tenthash.arx")).unwrap();
+    let rotations = spec.rotations();
+    assert_eq!(rotations.len(), 7);
+    assert_eq!(rotations[0], [16, 28]);
+    assert_eq!(rotations[6], [44, 13]);
+
+    // Changing a rotation changes the output, and changing it back restores
+    // the original mixer.
+    let input = [0x5a; tenthash::IN_SIZE_BYTES];
+    let mut expected = [0u8; tenthash::OUT_SIZE_BYTES];
+    let mut actual = [0u8; tenthash::OUT_SIZE_BYTES];
+    tenthash::mix_input(&input, &mut expected, 7);
+
+    let mut changed = rotations.clone();
+    changed[3][1] = 1;
+    spec.set_rotations(&changed);
+    spec.mix_input(&input, &mut actual, 7);
+    assert_ne!(actual, expected);
+
+    spec.set_rotations(&rotations);
+    spec.mix_input(&input, &mut actual, 7);
+    assert_eq!(actual, expected);
+}
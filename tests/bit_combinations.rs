@@ -0,0 +1,122 @@
+//! Checks `generate_bit_combinations_in()` against brute-force enumeration
+//! for small inputs.
+
+use lib::stats::{generate_bit_combinations, generate_bit_combinations_in, BitCombinations};
+
+fn as_u32(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .enumerate()
+        .fold(0, |n, (i, &b)| n | (b as u32) << (i * 8))
+}
+
+/// Checks that the enumeration is exactly the brute-force set of inputs, in
+/// order of weight, and wraps around after it.
+fn check(combinations: &BitCombinations, byte_len: usize) {
+    let bit_len = byte_len * 8;
+    let window = combinations.window_for(bit_len);
+    let window_mask: u32 = (window.start..window.end).map(|i| 1 << i).sum();
+    let mask = if bit_len == 32 {
+        u32::MAX
+    } else {
+        (1 << bit_len) - 1
+    };
+
+    let mut expected: Vec<u32> = (0..=mask)
+        .filter(|&n| n & !window_mask == 0)
+        .filter(|&n| {
+            let weight = n.count_ones() as usize;
+            weight >= combinations.min_weight && weight <= combinations.max_weight
+        })
+        .map(|n| if combinations.complement { n ^ mask } else { n })
+        .collect();
+    assert_eq!(combinations.count(bit_len), Some(expected.len()));
+
+    let generate = |index| {
+        let mut bytes = vec![0xaau8; byte_len];
+        generate_bit_combinations_in(index, &mut bytes, combinations);
+        as_u32(&bytes)
+    };
+    let mut actual: Vec<u32> = (0..expected.len()).map(generate).collect();
+
+    let weight = |n: &u32| {
+        let n = if combinations.complement {
+            n ^ mask
+        } else {
+            *n
+        };
+        n.count_ones()
+    };
+    assert!(actual.windows(2).all(|w| weight(&w[0]) <= weight(&w[1])));
+    for (i, &n) in actual.iter().enumerate().take(10) {
+        assert_eq!(generate(expected.len() + i), n);
+    }
+
+    actual.sort();
+    expected.sort();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn all() {
+    check(&BitCombinations::default(), 1);
+    check(&BitCombinations::default(), 2);
+}
+
+#[test]
+fn weight_ranges() {
+    for weight in 0..=17 {
+        check(&BitCombinations::exact(weight), 2);
+    }
+    for (min_weight, max_weight) in [(1, 3), (2, 2), (5, 16), (12, 100), (3, 1)] {
+        let combinations = BitCombinations {
+            min_weight,
+            max_weight,
+            ..BitCombinations::default()
+        };
+        check(&combinations, 2);
+    }
+}
+
+#[test]
+fn windows() {
+    for window in [0..16, 3..11, 8..9, 5..5, 12..40] {
+        for weight in 0..4 {
+            let combinations = BitCombinations {
+                window: Some(window.clone()),
+                ..BitCombinations::exact(weight)
+            };
+            check(&combinations, 2);
+        }
+    }
+}
+
+#[test]
+fn complemented() {
+    for window in [None, Some(4..13)] {
+        for (min_weight, max_weight) in [(0, usize::MAX), (2, 2), (1, 3)] {
+            let combinations = BitCombinations {
+                min_weight,
+                max_weight,
+                window: window.clone(),
+                complement: true,
+            };
+            check(&combinations, 2);
+        }
+    }
+}
+
+#[test]
+fn large_inputs() {
+    // Enough combinations to overflow a usize, which mustn't panic.
+    let combinations = BitCombinations::exact(3);
+    assert_eq!(combinations.count(512), Some(512 * 511 * 510 / 6));
+    assert_eq!(BitCombinations::default().count(512), None);
+
+    let mut bytes = [0u8; 64];
+    for index in [0, 1 << 16, usize::MAX] {
+        generate_bit_combinations(index, &mut bytes);
+        generate_bit_combinations_in(index, &mut bytes, &combinations);
+        assert_eq!(bytes.iter().map(|b| b.count_ones()).sum::<u32>(), 3);
+    }
+}
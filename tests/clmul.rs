@@ -0,0 +1,20 @@
+#![cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+
+use lib::mixers::clmul::{clmul_hw, clmul_soft};
+use nanorand::{Rng, WyRand};
+
+const ITERATIONS: usize = 100_000;
+
+#[test]
+fn clmul_soft_matches_hw() {
+    if !std::is_x86_feature_detected!("pclmulqdq") {
+        return;
+    }
+
+    let mut rng = WyRand::new_seed(0x3c2852bb91c300cb);
+    for _ in 0..ITERATIONS {
+        let a = rng.generate::<u64>();
+        let b = rng.generate::<u64>();
+        assert_eq!(clmul_soft(a, b), unsafe { clmul_hw(a, b) });
+    }
+}
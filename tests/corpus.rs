@@ -0,0 +1,83 @@
+//! Checks of corpus parsing and record placement.
+
+use lib::corpus::{Corpus, Padding};
+
+fn generate(corpus: &Corpus, index: usize, len: usize) -> Vec<u8> {
+    let mut bytes = vec![0xaau8; len];
+    corpus.generate(index, &mut bytes);
+    bytes
+}
+
+#[test]
+fn lines() {
+    let corpus = Corpus::from_lines(b"one\r\ntwo\n\nfour\n", Padding::Zero);
+    assert_eq!(corpus.len(), 4);
+    assert_eq!(corpus.record(0), b"one");
+    assert_eq!(corpus.record(1), b"two");
+    assert_eq!(corpus.record(2), b"");
+    assert_eq!(corpus.record(3), b"four");
+
+    // No trailing newline, and empty.
+    assert_eq!(Corpus::from_lines(b"a\nb", Padding::Zero).len(), 2);
+    assert!(Corpus::from_lines(b"", Padding::Zero).is_empty());
+}
+
+#[test]
+fn chunks() {
+    let corpus = Corpus::from_chunks(&[1, 2, 3, 4, 5, 6, 7], 3, Padding::Zero);
+    assert_eq!(corpus.len(), 3);
+    assert_eq!(corpus.record(1), [4, 5, 6]);
+    assert_eq!(corpus.record(2), [7]);
+}
+
+#[test]
+fn padding() {
+    let mut corpus = Corpus::from_lines(b"abc\n\nabcdefghij\n", Padding::Zero);
+    assert_eq!(generate(&corpus, 0, 8), b"abc\0\0\0\0\0");
+    assert_eq!(generate(&corpus, 1, 8), [0; 8]);
+    assert_eq!(generate(&corpus, 2, 8), b"abcdefgh");
+
+    corpus.padding = Padding::Repeat;
+    assert_eq!(generate(&corpus, 0, 8), b"abcabcab");
+    assert_eq!(generate(&corpus, 1, 8), [0; 8]);
+    assert_eq!(generate(&corpus, 2, 8), b"abcdefgh");
+
+    corpus.padding = Padding::LengthPrefixed;
+    assert_eq!(generate(&corpus, 0, 8), b"\x03\0\0\0abc\0");
+    assert_eq!(generate(&corpus, 1, 8), [0; 8]);
+    assert_eq!(generate(&corpus, 2, 8), b"\x0a\0\0\0abcd");
+    assert_eq!(generate(&corpus, 0, 2), [3, 0]);
+}
+
+#[test]
+fn offset() {
+    let mut corpus = Corpus::from_lines(b"abc\nabcdefghij\n", Padding::Zero);
+    corpus.offset = 3;
+    assert_eq!(generate(&corpus, 0, 8), b"\0\0\0abc\0\0");
+    assert_eq!(generate(&corpus, 1, 8), b"\0\0\0abcde");
+
+    corpus.padding = Padding::Repeat;
+    assert_eq!(generate(&corpus, 0, 8), b"\0\0\0abcab");
+
+    corpus.padding = Padding::LengthPrefixed;
+    assert_eq!(generate(&corpus, 0, 8), b"\0\0\0\x03\0\0\0a");
+
+    // Past the end of the input.
+    corpus.offset = 10;
+    assert_eq!(generate(&corpus, 0, 8), [0; 8]);
+}
+
+#[test]
+fn wraps_around() {
+    let corpus = Corpus::from_lines(b"x\ny\n", Padding::Zero);
+    assert_eq!(generate(&corpus, 2, 4), generate(&corpus, 0, 4));
+    assert_eq!(generate(&corpus, 5, 4), generate(&corpus, 1, 4));
+}
+
+#[test]
+fn padding_names() {
+    assert_eq!(Padding::parse("zero"), Ok(Padding::Zero));
+    assert_eq!(Padding::parse("repeat"), Ok(Padding::Repeat));
+    assert_eq!(Padding::parse("length"), Ok(Padding::LengthPrefixed));
+    assert!(Padding::parse("ones").is_err());
+}
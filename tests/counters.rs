@@ -0,0 +1,94 @@
+//! Checks of the counter input generators' layouts.
+
+use lib::stats::{
+    generate_counter, generate_counting, generate_gray_code, generate_gray_code_counter,
+    CounterLayout, Endian,
+};
+
+fn counter(index: usize, len: usize, layout: &CounterLayout) -> Vec<u8> {
+    let mut bytes = vec![0xaau8; len];
+    generate_counter(index, &mut bytes, layout);
+    bytes
+}
+
+#[test]
+fn default_layout() {
+    for index in [0, 1, 255, 256, 0x0123_4567_89ab_cdef] {
+        let mut bytes = vec![0xaau8; 16];
+        generate_counting(index, &mut bytes);
+        let mut expected = (index as u64).to_le_bytes().to_vec();
+        expected.resize(16, 0);
+        assert_eq!(bytes, expected);
+
+        generate_gray_code(index, &mut bytes);
+        let gray = (index ^ (index >> 1)) as u64;
+        assert_eq!(bytes[..8], gray.to_le_bytes());
+        assert!(bytes[8..].iter().all(|&b| b == 0));
+    }
+}
+
+#[test]
+fn offset_width_and_endianness() {
+    let layout = CounterLayout {
+        offset: 5,
+        width: 3,
+        endian: Endian::Big,
+        ..CounterLayout::default()
+    };
+    assert_eq!(
+        counter(0x0102_0304, 10, &layout),
+        [0, 0, 0, 0, 0, 0x02, 0x03, 0x04, 0, 0]
+    );
+
+    let layout = CounterLayout {
+        endian: Endian::Little,
+        ..layout
+    };
+    assert_eq!(
+        counter(0x0102_0304, 10, &layout),
+        [0, 0, 0, 0, 0, 0x04, 0x03, 0x02, 0, 0]
+    );
+}
+
+#[test]
+fn stride_and_start() {
+    let layout = CounterLayout {
+        stride: 16,
+        start: 0x1000,
+        ..CounterLayout::default()
+    };
+    for index in 0..100 {
+        let value = 0x1000 + 16 * index as u64;
+        assert_eq!(counter(index, 8, &layout), value.to_le_bytes());
+    }
+
+    // The gray code is of the counter's value, not the index.
+    let mut bytes = [0u8; 8];
+    generate_gray_code_counter(3, &mut bytes, &layout);
+    let value = 0x1000 + 16 * 3u64;
+    assert_eq!(bytes, (value ^ (value >> 1)).to_le_bytes());
+}
+
+#[test]
+fn short_inputs() {
+    // Narrowed to fit, keeping the low bytes.
+    let mut bytes = [0u8; 4];
+    generate_counting(0x0102_0304_0506, &mut bytes);
+    assert_eq!(bytes, [0x06, 0x05, 0x04, 0x03]);
+    generate_gray_code(1, &mut bytes[..1]);
+    assert_eq!(bytes[..1], [1]);
+
+    let layout = CounterLayout {
+        offset: 2,
+        endian: Endian::Big,
+        ..CounterLayout::default()
+    };
+    assert_eq!(counter(0x0102_0304, 4, &layout), [0, 0, 0x03, 0x04]);
+
+    // Entirely past the end.
+    let layout = CounterLayout {
+        offset: 8,
+        ..CounterLayout::default()
+    };
+    assert_eq!(counter(12345, 8, &layout), [0; 8]);
+}
@@ -0,0 +1,22 @@
+#![cfg(target_arch = "x86_64")]
+
+use lib::mixers::crc32c::{crc32c_u64_hw, crc32c_u64_soft};
+use nanorand::{Rng, WyRand};
+
+const ITERATIONS: usize = 100_000;
+
+#[test]
+fn crc32c_u64_soft_matches_hw() {
+    if !std::is_x86_feature_detected!("sse4.2") {
+        return;
+    }
+
+    let mut rng = WyRand::new_seed(0x88d0658b1b532ea3);
+    for _ in 0..ITERATIONS {
+        let crc = rng.generate::<u32>();
+        let data = rng.generate::<u64>();
+        assert_eq!(crc32c_u64_soft(crc, data), unsafe {
+            crc32c_u64_hw(crc, data)
+        });
+    }
+}
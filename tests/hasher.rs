@@ -0,0 +1,87 @@
+use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hash, Hasher};
+
+use lib::mixers::hasher::{self, WriteStrategy};
+use nanorand::{Rng, WyRand};
+
+const ITERATIONS: usize = 1000;
+
+type Build = BuildHasherDefault<DefaultHasher>;
+
+fn random_input(rng: &mut WyRand) -> [u8; hasher::IN_SIZE_BYTES] {
+    let mut input = [0u8; hasher::IN_SIZE_BYTES];
+    rng.fill_bytes(&mut input);
+    input
+}
+
+/// The adapter's output is the hasher's own output for the same writes.
+fn check_matches_direct_hashing<B: BuildHasher + Default>(seed: u64) {
+    let mut rng = WyRand::new_seed(seed);
+    for _ in 0..ITERATIONS {
+        let input = random_input(&mut rng);
+
+        let mut out = [0u8; hasher::OUT_SIZE_BYTES];
+        hasher::mix_input::<B>(&input, &mut out, 1, WriteStrategy::Write);
+        let mut h = B::default().build_hasher();
+        h.write(&input);
+        assert_eq!(u64::from_le_bytes(out), h.finish());
+
+        hasher::mix_input::<B>(&input, &mut out, 1, WriteStrategy::Hash);
+        assert_eq!(u64::from_le_bytes(out), B::default().hash_one(&input[..]));
+
+        hasher::mix_input::<B>(&input, &mut out, 1, WriteStrategy::WriteU64);
+        let mut h = B::default().build_hasher();
+        h.write_u64(u64::from_le_bytes(input[0..8].try_into().unwrap()));
+        h.write_u64(u64::from_le_bytes(input[8..16].try_into().unwrap()));
+        assert_eq!(u64::from_le_bytes(out), h.finish());
+    }
+}
+
+#[test]
+fn matches_direct_hashing() {
+    check_matches_direct_hashing::<Build>(0x5f1e5bbcd2f5d3a0);
+}
+
+#[test]
+fn matches_direct_hashing_ahash() {
+    check_matches_direct_hashing::<BuildHasherDefault<ahash::AHasher>>(0x8e2b47d1c90a36f5);
+}
+
+#[test]
+fn matches_direct_hashing_foldhash() {
+    check_matches_direct_hashing::<foldhash::fast::FixedState>(0x1c74e0b98f3d2a65);
+}
+
+#[test]
+fn matches_direct_hashing_fxhash() {
+    check_matches_direct_hashing::<rustc_hash::FxBuildHasher>(0xa93f6d0257e1b84c);
+}
+
+/// Each extra output word is the hash under its own seed, and later rounds
+/// write zeros.
+#[test]
+fn seeds_and_rounds() {
+    let mut rng = WyRand::new_seed(0x0d6a1f3be2c94870);
+    for _ in 0..ITERATIONS {
+        let input = random_input(&mut rng);
+        let rounds = rng.generate_range(0..4usize);
+
+        let mut out = [0u8; hasher::WIDE_OUT_SIZE_BYTES];
+        hasher::mix_input::<Build>(&input, &mut out, rounds, WriteStrategy::WriteU8);
+
+        for (seed, word) in out.chunks_exact(8).enumerate() {
+            let mut h = Build::default().build_hasher();
+            if seed > 0 {
+                h.write_u64(seed as u64);
+            }
+            for round in 0..rounds {
+                let block = if round == 0 {
+                    input
+                } else {
+                    [0; hasher::IN_SIZE_BYTES]
+                };
+                block.iter().for_each(|b| b.hash(&mut h));
+            }
+            assert_eq!(u64::from_le_bytes(word.try_into().unwrap()), h.finish());
+        }
+    }
+}
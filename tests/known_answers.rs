@@ -0,0 +1,1214 @@
+//! Known-answer tests for the mixers, to catch transcription errors.
+//!
+//! Where a mixer's state can be driven into a real hash, the mixer is checked
+//! by finishing the hash by hand from its output and comparing against an
+//! independent implementation of the full hash.  Where the mixer starts from a
+//! state the real hash can't produce (e.g. a 64-bit seed where the reference
+//! only takes a 32-bit one), the test has its own straightforward reference
+//! implementation of the hash, which is first checked against the independent
+//! implementation, and the mixer is then checked against the reference's state
+//! after absorbing the same blocks.
+//!
+//! Not covered: Goodhart (defined by this repo), the bare Skein MIX rounds
+//! (no key schedule), and AquaHash (no independent implementation or
+//! vectors available to check against).
+
+use lib::aes_round::aesenc;
+use lib::mixers::{self, finalizers};
+use nanorand::{Rng, WyRand};
+
+const ITERATIONS: usize = 1000;
+
+/// Runs a mixer and returns its output.
+fn mix(
+    mix_input: fn(&[u8], &mut [u8], usize),
+    input: &[u8],
+    out_size: usize,
+    rounds: usize,
+) -> Vec<u8> {
+    let mut out = vec![0u8; out_size];
+    mix_input(input, &mut out, rounds);
+    out
+}
+
+/// The input block followed by enough zeros to make `len` bytes, which is
+/// what the accumulators absorb when running for more than one round.
+fn zero_padded(block: &[u8], len: usize) -> Vec<u8> {
+    let mut data = block.to_vec();
+    data.resize(len, 0);
+    data
+}
+
+fn random_bytes(rng: &mut WyRand, len: usize) -> Vec<u8> {
+    (0..len).map(|_| rng.generate::<u8>()).collect()
+}
+
+fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..(offset + 8)].try_into().unwrap())
+}
+
+fn u64s(bytes: &[u8]) -> Vec<u64> {
+    bytes.chunks(8).map(|chunk| u64_at(chunk, 0)).collect()
+}
+
+//-------------------------------------------------------------
+// AES
+
+/// The first round of the AES-128 example in FIPS-197 Appendix B.
+#[test]
+fn aes_round_fips197() {
+    let state = u128::from_le_bytes([
+        0x19, 0x3d, 0xe3, 0xbe, 0xa0, 0xf4, 0xe2, 0x2b, 0x9a, 0xc6, 0x8d, 0x2a, 0xe9, 0xf8, 0x48,
+        0x08,
+    ]);
+    let key = u128::from_le_bytes([
+        0xa0, 0xfa, 0xfe, 0x17, 0x88, 0x54, 0x2c, 0xb1, 0x23, 0xa3, 0x39, 0x39, 0x2a, 0x6c, 0x76,
+        0x05,
+    ]);
+    let expected = u128::from_le_bytes([
+        0xa4, 0x9c, 0x7f, 0xf2, 0x68, 0x9f, 0x35, 0x2b, 0x6b, 0x5b, 0xea, 0x43, 0x02, 0x6a, 0x50,
+        0x49,
+    ]);
+
+    assert_eq!(aesenc(state, key), expected);
+    assert_eq!(lib::aes_round::aesenc_soft(state, key), expected);
+}
+
+//-------------------------------------------------------------
+// TentHash
+
+/// A single 32-byte block hashed by the reference TentHash, finished by hand
+/// from the mixer.
+#[test]
+fn tenthash() {
+    use mixers::tenthash::*;
+    const INIT: [u64; 4] = [
+        0x5d6daffc4411a967,
+        0xe22d4dea68577f34,
+        0xca50864d814cbc2e,
+        0x894e29b9611eb173,
+    ];
+
+    let mut rng = WyRand::new_seed(0x1896c105404b2ea0);
+    for _ in 0..ITERATIONS {
+        let block = random_bytes(&mut rng, IN_SIZE_BYTES);
+
+        let mut state = [0u8; 32];
+        for i in 0..4 {
+            let word = INIT[i] ^ u64_at(&block, i * 8);
+            state[(i * 8)..((i + 1) * 8)].copy_from_slice(&word.to_le_bytes());
+        }
+        let mut state = mix(mix_input, &state, OUT_SIZE_BYTES, DEFAULT_ROUNDS);
+
+        // Finalization: xor in the message length in bits, and mix twice.
+        let word = u64_at(&state, 0) ^ (IN_SIZE_BYTES as u64 * 8);
+        state[0..8].copy_from_slice(&word.to_le_bytes());
+        let state = mix(mix_input, &state, OUT_SIZE_BYTES, DEFAULT_ROUNDS * 2);
+
+        assert_eq!(state[0..DIGEST_SIZE_BYTES], ::tenthash::hash(&block));
+    }
+}
+
+//-------------------------------------------------------------
+// FNV-1a
+
+#[test]
+fn fnv1a() {
+    use mixers::fnv1a::*;
+
+    let mut rng = WyRand::new_seed(0x06805b053507e7f3);
+    for _ in 0..ITERATIONS {
+        let block = random_bytes(&mut rng, IN_SIZE_BYTES);
+        for rounds in 1..=3 {
+            let data = zero_padded(&block, IN_SIZE_BYTES * rounds);
+            let expected = const_fnv1a_hash::fnv1a_hash_128(&data, None);
+            assert_eq!(
+                mix(mix_input, &block, OUT_SIZE_BYTES, rounds),
+                expected.to_le_bytes()
+            );
+        }
+    }
+}
+
+//-------------------------------------------------------------
+// MeowHash
+
+/// A 256-byte input (one full MeowHash block, with our block at the start)
+/// hashed by the reference MeowHash, finished by hand from the mixer.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[test]
+fn meowhash() {
+    use lib::aes_round::aesdec;
+    use meowhash::MeowHasher;
+    use mixers::meowhash::*;
+
+    if !std::is_x86_feature_detected!("aes") {
+        return;
+    }
+
+    fn paddq(a: u128, b: u128) -> u128 {
+        let lo = (a as u64).wrapping_add(b as u64);
+        let hi = ((a >> 64) as u64).wrapping_add((b >> 64) as u64);
+        ((hi as u128) << 64) | lo as u128
+    }
+
+    let mut rng = WyRand::new_seed(0x10a7c61c8f5cc2a0);
+    for _ in 0..ITERATIONS {
+        let block = random_bytes(&mut rng, IN_SIZE_BYTES);
+        let out = mix(mix_input, &block, OUT_SIZE_BYTES, 8);
+        let mut xmm: [u128; 8] = std::array::from_fn(|i| {
+            u128::from_le_bytes(out[(i * 16)..((i + 1) * 16)].try_into().unwrap())
+        });
+
+        // Finalization for a 256-byte input.  There are no residual bytes, so
+        // the first `mix_reg` only does the two `aesdec`s, and the second one
+        // mixes in the length.
+        let len = 256u128;
+        xmm[0] = aesdec(xmm[0], xmm[4]);
+        xmm[4] = aesdec(xmm[4], xmm[1]);
+        xmm[1] = aesdec(xmm[1], xmm[5]);
+        xmm[7] = paddq(xmm[7], len >> 120);
+        xmm[5] = aesdec(xmm[5], xmm[2]);
+        xmm[3] = paddq(xmm[3], len >> 8);
+        xmm[2] ^= len;
+
+        for i in 0..12 {
+            let [r1, r2, r3, r4, r5, r6] = [0, 1, 2, 4, 5, 6].map(|r| (r + i) % 8);
+            xmm[r1] = aesdec(xmm[r1], xmm[r4]);
+            xmm[r2] = paddq(xmm[r2], xmm[r5]);
+            xmm[r4] ^= xmm[r6];
+            xmm[r4] = aesdec(xmm[r4], xmm[r2]);
+            xmm[r5] = paddq(xmm[r5], xmm[r6]);
+            xmm[r2] ^= xmm[r3];
+        }
+
+        let hash: Vec<u8> = xmm.iter().flat_map(|r| r.to_le_bytes()).collect();
+        let expected = MeowHasher::hash(&zero_padded(&block, 256)).into_bytes();
+        assert_eq!(hash, expected);
+    }
+}
+
+//-------------------------------------------------------------
+// XXH3
+
+/// The default XXH3 secret, from the specification.
+#[rustfmt::skip]
+const XXH3_SECRET: [u8; 192] = [
+    0xb8, 0xfe, 0x6c, 0x39, 0x23, 0xa4, 0x4b, 0xbe, 0x7c, 0x01, 0x81, 0x2c, 0xf7, 0x21, 0xad, 0x1c,
+    0xde, 0xd4, 0x6d, 0xe9, 0x83, 0x90, 0x97, 0xdb, 0x72, 0x40, 0xa4, 0xa4, 0xb7, 0xb3, 0x67, 0x1f,
+    0xcb, 0x79, 0xe6, 0x4e, 0xcc, 0xc0, 0xe5, 0x78, 0x82, 0x5a, 0xd0, 0x7d, 0xcc, 0xff, 0x72, 0x21,
+    0xb8, 0x08, 0x46, 0x74, 0xf7, 0x43, 0x24, 0x8e, 0xe0, 0x35, 0x90, 0xe6, 0x81, 0x3a, 0x26, 0x4c,
+    0x3c, 0x28, 0x52, 0xbb, 0x91, 0xc3, 0x00, 0xcb, 0x88, 0xd0, 0x65, 0x8b, 0x1b, 0x53, 0x2e, 0xa3,
+    0x71, 0x64, 0x48, 0x97, 0xa2, 0x0d, 0xf9, 0x4e, 0x38, 0x19, 0xef, 0x46, 0xa9, 0xde, 0xac, 0xd8,
+    0xa8, 0xfa, 0x76, 0x3f, 0xe3, 0x9c, 0x34, 0x3f, 0xf9, 0xdc, 0xbb, 0xc7, 0xc7, 0x0b, 0x4f, 0x1d,
+    0x8a, 0x51, 0xe0, 0x4b, 0xcd, 0xb4, 0x59, 0x31, 0xc8, 0x9f, 0x7e, 0xc9, 0xd9, 0x78, 0x73, 0x64,
+    0xea, 0xc5, 0xac, 0x83, 0x34, 0xd3, 0xeb, 0xc3, 0xc5, 0x81, 0xa0, 0xff, 0xfa, 0x13, 0x63, 0xeb,
+    0x17, 0x0d, 0xdd, 0x51, 0xb7, 0xf0, 0xda, 0x49, 0xd3, 0x16, 0x55, 0x26, 0x29, 0xd4, 0x68, 0x9e,
+    0x2b, 0x16, 0xbe, 0x58, 0x7d, 0x47, 0xa1, 0xfc, 0x8f, 0xf8, 0xb8, 0xd1, 0x7a, 0xd0, 0x31, 0xce,
+    0x45, 0xcb, 0x3a, 0x8f, 0x95, 0x16, 0x04, 0x28, 0xaf, 0xd7, 0xfb, 0xca, 0xbb, 0x4b, 0x40, 0x7e,
+];
+
+/// Finishes XXH3-64 by hand from the accumulators after all but the last
+/// stripe of a `len`-byte input whose remaining bytes are all zeros.
+fn xxhash3_finish(mut acc: Vec<u64>, len: usize) -> u64 {
+    let secret = &XXH3_SECRET;
+    let mul128_fold64 = |a: u64, b: u64| {
+        let product = a as u128 * b as u128;
+        (product as u64) ^ (product >> 64) as u64
+    };
+
+    // The last stripe is the final 64 bytes of input (all zeros here),
+    // with the secret at `secret.len() - 64 - 7`.
+    for (i, a) in acc.iter_mut().enumerate() {
+        let value = u64_at(secret, secret.len() - 71 + i * 8);
+        *a = a.wrapping_add((value & 0xffffffff) * (value >> 32));
+    }
+
+    // Merge the accumulators.
+    let mut h = (len as u64).wrapping_mul(0x9E3779B185EBCA87);
+    for i in 0..4 {
+        h = h.wrapping_add(mul128_fold64(
+            acc[i * 2] ^ u64_at(secret, 11 + i * 16),
+            acc[i * 2 + 1] ^ u64_at(secret, 11 + i * 16 + 8),
+        ));
+    }
+    finalizers::xxh3_avalanche(h)
+}
+
+/// A 256-byte input (three stripes plus the overlapping last stripe) hashed
+/// by the reference XXH3-64, finished by hand from the mixer.
+#[test]
+fn xxhash3() {
+    use mixers::xxhash3::*;
+    const LEN: usize = 256;
+
+    let mut rng = WyRand::new_seed(0x06b6c1596f23e0f0);
+    for _ in 0..ITERATIONS {
+        let block = random_bytes(&mut rng, IN_SIZE_BYTES);
+        let acc = u64s(&mix(mix_input, &block, OUT_SIZE_BYTES, 3));
+        let expected = xxhash_rust::xxh3::xxh3_64(&zero_padded(&block, LEN));
+        assert_eq!(xxhash3_finish(acc, LEN), expected);
+    }
+}
+
+/// A 1088-byte input (one full block, scrambled, plus the last stripe) hashed
+/// by the reference XXH3-64, finished by hand from the mixer.
+#[test]
+fn xxhash3_scrambled() {
+    use mixers::xxhash3::*;
+    const LEN: usize = IN_SIZE_BYTES * (STRIPES_PER_BLOCK + 1);
+
+    let mut rng = WyRand::new_seed(0x45cb3a8f95160428);
+    for _ in 0..ITERATIONS {
+        let block = random_bytes(&mut rng, IN_SIZE_BYTES);
+        let acc = u64s(&mix(
+            mix_input_scrambled,
+            &block,
+            OUT_SIZE_BYTES,
+            STRIPES_PER_BLOCK,
+        ));
+        let expected = xxhash_rust::xxh3::xxh3_64(&zero_padded(&block, LEN));
+        assert_eq!(xxhash3_finish(acc, LEN), expected);
+    }
+}
+
+//-------------------------------------------------------------
+// xxHash64
+
+/// Inputs of 1-3 stripes hashed by the reference xxHash64, finished by hand
+/// from the mixer.
+#[test]
+fn xxhash64() {
+    use mixers::xxhash64::*;
+    const PRIME64_1: u64 = 0x9E3779B185EBCA87;
+    const PRIME64_2: u64 = 0xC2B2AE3D27D4EB4F;
+    const PRIME64_4: u64 = 0x85EBCA77C2B2AE63;
+
+    let mut rng = WyRand::new_seed(0xafd7fbcabb4b407e);
+    for _ in 0..ITERATIONS {
+        // Several stripes of real input, followed by zeros.
+        let stripes = rng.generate_range(1..=3usize);
+        let block = random_bytes(&mut rng, IN_SIZE_BYTES * stripes);
+        for rounds in stripes..=(stripes + 2) {
+            let v = u64s(&mix(mix_input, &block, OUT_SIZE_BYTES, rounds));
+
+            let mut h = v[0]
+                .rotate_left(1)
+                .wrapping_add(v[1].rotate_left(7))
+                .wrapping_add(v[2].rotate_left(12))
+                .wrapping_add(v[3].rotate_left(18));
+            for lane in v {
+                let lane = lane
+                    .wrapping_mul(PRIME64_2)
+                    .rotate_left(31)
+                    .wrapping_mul(PRIME64_1);
+                h = (h ^ lane).wrapping_mul(PRIME64_1).wrapping_add(PRIME64_4);
+            }
+            h = h.wrapping_add((IN_SIZE_BYTES * rounds) as u64);
+
+            let data = zero_padded(&block, IN_SIZE_BYTES * rounds);
+            let expected = xxhash_rust::xxh64::xxh64(&data, 0);
+            assert_eq!(finalizers::xxh64_avalanche(h), expected);
+        }
+    }
+}
+
+//-------------------------------------------------------------
+// Murmur3
+
+const MURMUR3_C1: u64 = 0x87c37b91114253d5;
+const MURMUR3_C2: u64 = 0x4cf5ad432745937f;
+
+fn murmur3_absorb(h: &mut [u64; 2], block: &[u8]) {
+    let k1 = u64_at(block, 0);
+    let k2 = u64_at(block, 8);
+
+    h[0] ^= k1
+        .wrapping_mul(MURMUR3_C1)
+        .rotate_left(31)
+        .wrapping_mul(MURMUR3_C2);
+    h[0] = h[0]
+        .rotate_left(27)
+        .wrapping_add(h[1])
+        .wrapping_mul(5)
+        .wrapping_add(0x52dce729);
+    h[1] ^= k2
+        .wrapping_mul(MURMUR3_C2)
+        .rotate_left(33)
+        .wrapping_mul(MURMUR3_C1);
+    h[1] = h[1]
+        .rotate_left(31)
+        .wrapping_add(h[0])
+        .wrapping_mul(5)
+        .wrapping_add(0x38495ab5);
+}
+
+/// MurmurHash3_x64_128 for inputs that are a multiple of 16 bytes.
+fn reference_murmur3_x64_128(data: &[u8], seed: u64) -> u128 {
+    let fmix64 = |mut k: u64| {
+        k ^= k >> 33;
+        k = k.wrapping_mul(0xff51afd7ed558ccd);
+        k ^= k >> 33;
+        k = k.wrapping_mul(0xc4ceb9fe1a85ec53);
+        k ^ (k >> 33)
+    };
+
+    let mut h = [seed, seed];
+    for block in data.chunks(16) {
+        murmur3_absorb(&mut h, block);
+    }
+    h[0] ^= data.len() as u64;
+    h[1] ^= data.len() as u64;
+    h[0] = h[0].wrapping_add(h[1]);
+    h[1] = h[1].wrapping_add(h[0]);
+    h[0] = fmix64(h[0]);
+    h[1] = fmix64(h[1]);
+    h[0] = h[0].wrapping_add(h[1]);
+    h[1] = h[1].wrapping_add(h[0]);
+
+    ((h[1] as u128) << 64) | h[0] as u128
+}
+
+#[test]
+fn murmur3_reference() {
+    let mut rng = WyRand::new_seed(0x864b58dfc6b7d377);
+    for _ in 0..ITERATIONS {
+        let seed = rng.generate::<u32>();
+        let len = 16 * rng.generate_range(0..8usize);
+        let data = random_bytes(&mut rng, len);
+        let expected = ::murmur3::murmur3_x64_128(&mut &data[..], seed).unwrap();
+        assert_eq!(reference_murmur3_x64_128(&data, seed as u64), expected);
+    }
+}
+
+#[test]
+fn murmur3() {
+    use mixers::murmur3::*;
+    const SEED: u64 = 0xe9e58282f1c2287e;
+
+    let mut rng = WyRand::new_seed(0x14ed774d389a2665);
+    for _ in 0..ITERATIONS {
+        // Several blocks of real input, followed by zeros.
+        let blocks = rng.generate_range(1..=3usize);
+        let block = random_bytes(&mut rng, IN_SIZE_BYTES * blocks);
+        for rounds in blocks..=(blocks + 2) {
+            let mut h = [SEED, SEED];
+            for block in zero_padded(&block, IN_SIZE_BYTES * rounds).chunks(16) {
+                murmur3_absorb(&mut h, block);
+            }
+            // The mixer outputs `h2` first.
+            assert_eq!(
+                u64s(&mix(mix_input, &block, OUT_SIZE_BYTES, rounds)),
+                [h[1], h[0]]
+            );
+        }
+    }
+}
+
+//-------------------------------------------------------------
+// MetroHash128
+
+const METRO_K: [u64; 4] = [0xC83A91E1, 0x8648DBDB, 0x7BDEC03B, 0x2F5870A5];
+
+fn metrohash128_absorb(v: &mut [u64; 4], block: &[u8]) {
+    for i in 0..4 {
+        v[i] = v[i].wrapping_add(u64_at(block, i * 8).wrapping_mul(METRO_K[i]));
+        v[i] = v[i].rotate_right(29).wrapping_add(v[(i + 2) % 4]);
+    }
+}
+
+/// MetroHash128 for inputs that are a non-zero multiple of 32 bytes.
+fn reference_metrohash128(data: &[u8], seed: u64) -> (u64, u64) {
+    let [k0, k1, k2, k3] = METRO_K;
+    let mut v = [
+        seed.wrapping_sub(k0).wrapping_mul(k3),
+        seed.wrapping_add(k1).wrapping_mul(k2),
+        seed.wrapping_add(k0).wrapping_mul(k2),
+        seed.wrapping_sub(k1).wrapping_mul(k3),
+    ];
+    for block in data.chunks(32) {
+        metrohash128_absorb(&mut v, block);
+    }
+
+    v[2] ^= (v[0].wrapping_add(v[3]).wrapping_mul(k0).wrapping_add(v[1]))
+        .rotate_right(21)
+        .wrapping_mul(k1);
+    v[3] ^= (v[1].wrapping_add(v[2]).wrapping_mul(k1).wrapping_add(v[0]))
+        .rotate_right(21)
+        .wrapping_mul(k0);
+    v[0] ^= (v[0].wrapping_add(v[2]).wrapping_mul(k0).wrapping_add(v[3]))
+        .rotate_right(21)
+        .wrapping_mul(k1);
+    v[1] ^= (v[1].wrapping_add(v[3]).wrapping_mul(k1).wrapping_add(v[2]))
+        .rotate_right(21)
+        .wrapping_mul(k0);
+
+    v[0] = v[0].wrapping_add((v[0].wrapping_mul(k0).wrapping_add(v[1])).rotate_right(13));
+    v[1] = v[1].wrapping_add((v[1].wrapping_mul(k1).wrapping_add(v[0])).rotate_right(37));
+    v[0] = v[0].wrapping_add((v[0].wrapping_mul(k2).wrapping_add(v[1])).rotate_right(13));
+    v[1] = v[1].wrapping_add((v[1].wrapping_mul(k3).wrapping_add(v[0])).rotate_right(37));
+
+    (v[0], v[1])
+}
+
+#[test]
+fn metrohash128_reference() {
+    use std::hash::Hasher;
+
+    let mut rng = WyRand::new_seed(0x5c1aeb42b567cd13);
+    for _ in 0..ITERATIONS {
+        let seed = rng.generate::<u64>();
+        let len = 32 * rng.generate_range(1..8usize);
+        let data = random_bytes(&mut rng, len);
+        let mut hasher = ::metrohash::MetroHash128::with_seed(seed);
+        hasher.write(&data);
+        assert_eq!(reference_metrohash128(&data, seed), hasher.finish128());
+    }
+}
+
+#[test]
+fn metrohash128() {
+    use mixers::metrohash128::*;
+
+    let mut rng = WyRand::new_seed(0xc8efabd49aa8ee35);
+    for _ in 0..ITERATIONS {
+        let block = random_bytes(&mut rng, IN_SIZE_BYTES);
+        for rounds in 1..=3 {
+            // The mixer starts from an all-zero state, which no seed produces.
+            let mut v = [0u64; 4];
+            for block in zero_padded(&block, IN_SIZE_BYTES * rounds).chunks(32) {
+                metrohash128_absorb(&mut v, block);
+            }
+            assert_eq!(u64s(&mix(mix_input, &block, OUT_SIZE_BYTES, rounds)), v);
+        }
+    }
+}
+
+//-------------------------------------------------------------
+// CityHash128
+
+const CITY_K0: u64 = 0xc3a5c85c97cb3127;
+const CITY_K1: u64 = 0xb492b66fbe98f273;
+
+/// CityHash128's state: `[v.0, v.1, w.0, w.1, x, y, z]`.
+type CityState = [u64; 7];
+
+fn city_weak_hash_len_32_with_seeds(s: &[u8], mut a: u64, mut b: u64) -> (u64, u64) {
+    let w = u64_at(s, 0);
+    let x = u64_at(s, 8);
+    let y = u64_at(s, 16);
+    let z = u64_at(s, 24);
+
+    a = a.wrapping_add(w);
+    b = b.wrapping_add(a).wrapping_add(z).rotate_right(21);
+    let c = a;
+    a = a.wrapping_add(x).wrapping_add(y);
+    b = b.wrapping_add(a.rotate_right(44));
+    (a.wrapping_add(z), b.wrapping_add(c))
+}
+
+/// The state of CityHash128WithSeed on a `len`-byte input `s` after its main
+/// loop has absorbed `steps` 64-byte chunks.  (The real loop is unrolled to two
+/// chunks.)  `s` may extend past `len` with zeros, since the initial state
+/// always reads byte 88.
+fn city_state(s: &[u8], len: usize, seed: (u64, u64), steps: usize) -> CityState {
+    let k1 = CITY_K1;
+    let mut x = seed.0;
+    let mut y = seed.1;
+    let mut z = (len as u64).wrapping_mul(k1);
+    let v0 = (y ^ k1)
+        .rotate_right(49)
+        .wrapping_mul(k1)
+        .wrapping_add(u64_at(s, 0));
+    let mut v = (
+        v0,
+        v0.rotate_right(42)
+            .wrapping_mul(k1)
+            .wrapping_add(u64_at(s, 8)),
+    );
+    let mut w = (
+        y.wrapping_add(z)
+            .rotate_right(35)
+            .wrapping_mul(k1)
+            .wrapping_add(x),
+        x.wrapping_add(u64_at(s, 88))
+            .rotate_right(53)
+            .wrapping_mul(k1),
+    );
+
+    for chunk in s.chunks(64).take(steps) {
+        x = x
+            .wrapping_add(y)
+            .wrapping_add(v.0)
+            .wrapping_add(u64_at(chunk, 8))
+            .rotate_right(37)
+            .wrapping_mul(k1);
+        y = y
+            .wrapping_add(v.1)
+            .wrapping_add(u64_at(chunk, 48))
+            .rotate_right(42)
+            .wrapping_mul(k1);
+        x ^= w.1;
+        y = y.wrapping_add(v.0).wrapping_add(u64_at(chunk, 40));
+        z = z.wrapping_add(w.0).rotate_right(33).wrapping_mul(k1);
+        v = city_weak_hash_len_32_with_seeds(chunk, v.1.wrapping_mul(k1), x.wrapping_add(w.0));
+        w = city_weak_hash_len_32_with_seeds(
+            &chunk[32..],
+            z.wrapping_add(w.1),
+            y.wrapping_add(u64_at(chunk, 16)),
+        );
+        std::mem::swap(&mut z, &mut x);
+    }
+
+    [v.0, v.1, w.0, w.1, x, y, z]
+}
+
+/// CityHash128 (v1.1) for inputs of at least 144 bytes.
+fn reference_cityhash128(data: &[u8]) -> u128 {
+    let s = &data[16..];
+    let steps = (s.len() / 128) * 2;
+    city_finish(s, steps, city_state(s, s.len(), city_seed(data), steps))
+}
+
+/// The seed CityHash128 derives from the first 16 bytes of the input, for
+/// the rest of the input.
+fn city_seed(data: &[u8]) -> (u64, u64) {
+    (u64_at(data, 0), u64_at(data, 8).wrapping_add(CITY_K0))
+}
+
+/// The rest of CityHash128 for input `s`, after the main loop has absorbed
+/// `steps` chunks into `state`.
+fn city_finish(s: &[u8], steps: usize, state: [u64; 7]) -> u128 {
+    let k0 = CITY_K0;
+    let hash_len_16 = finalizers::city_hash_len_16;
+
+    let [v0, v1, w0, w1, mut x, mut y, mut z] = state;
+    let (mut v, mut w) = ((v0, v1), (w0, w1));
+
+    x = x.wrapping_add(v.0.wrapping_add(z).rotate_right(49).wrapping_mul(k0));
+    y = y.wrapping_mul(k0).wrapping_add(w.1.rotate_right(37));
+    z = z.wrapping_mul(k0).wrapping_add(w.0.rotate_right(27));
+    w.0 = w.0.wrapping_mul(9);
+    v.0 = v.0.wrapping_mul(k0);
+
+    // Hash up to 4 chunks of 32 bytes from the end of the input.
+    let tail_len = s.len() - steps * 64;
+    let mut tail_done = 0;
+    while tail_done < tail_len {
+        tail_done += 32;
+        let end = &s[(s.len() - tail_done)..];
+        y = x
+            .wrapping_add(y)
+            .rotate_right(42)
+            .wrapping_mul(k0)
+            .wrapping_add(v.1);
+        w.0 = w.0.wrapping_add(u64_at(end, 16));
+        x = x.wrapping_mul(k0).wrapping_add(w.0);
+        z = z.wrapping_add(w.1).wrapping_add(u64_at(end, 0));
+        w.1 = w.1.wrapping_add(v.0);
+        v = city_weak_hash_len_32_with_seeds(end, v.0.wrapping_add(z), v.1);
+        v.0 = v.0.wrapping_mul(k0);
+    }
+
+    x = hash_len_16(x, v.0);
+    y = hash_len_16(y.wrapping_add(z), w.0);
+    let first = hash_len_16(x.wrapping_add(v.1), w.1).wrapping_add(y);
+    let second = hash_len_16(x.wrapping_add(w.1), y.wrapping_add(v.1));
+
+    ((first as u128) << 64) | second as u128
+}
+
+#[test]
+fn cityhash128_reference() {
+    let mut rng = WyRand::new_seed(0x605384a744a6c1fa);
+    for _ in 0..ITERATIONS {
+        let len = rng.generate_range(144..1024usize);
+        let data = random_bytes(&mut rng, len);
+        assert_eq!(
+            reference_cityhash128(&data),
+            cityhash_rs::cityhash_110_128(&data)
+        );
+    }
+}
+
+#[test]
+fn cityhash128() {
+    use mixers::cityhash128::*;
+    const SEED: (u64, u64) = (0x6cfd5fc33eb025ed, 0x22db8460f81d5fea);
+
+    let mut rng = WyRand::new_seed(0xd9e32ec187f79cf0);
+    for _ in 0..ITERATIONS {
+        let block = random_bytes(&mut rng, IN_SIZE_BYTES);
+        for rounds in 1..=3 {
+            let len = IN_SIZE_BYTES * rounds;
+            let expected = city_state(&zero_padded(&block, len.max(96)), len, SEED, rounds);
+            assert_eq!(
+                u64s(&mix(mix_input, &block, OUT_SIZE_BYTES, rounds)),
+                expected
+            );
+        }
+    }
+}
+
+/// Full CityHash128s, with the main loop run by the mixer from the initial
+/// state rather than by the reference, so that the mixer's state layout is
+/// checked against the reference crate.
+#[test]
+fn cityhash128_from_state() {
+    use mixers::cityhash128::*;
+
+    let mut rng = WyRand::new_seed(0x3a9d07c5e16b48f2);
+    for _ in 0..ITERATIONS {
+        let len = rng.generate_range(144..1024usize);
+        let data = random_bytes(&mut rng, len);
+        let s = &data[16..];
+        let steps = (s.len() / 128) * 2;
+
+        let initial: Vec<u8> = city_state(s, s.len(), city_seed(&data), 0)
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect();
+        let mut out = vec![0u8; OUT_SIZE_BYTES];
+        mix_input_from_state(&initial, &s[..(steps * IN_SIZE_BYTES)], &mut out, steps);
+        let state: [u64; 7] = u64s(&out).try_into().unwrap();
+
+        assert_eq!(
+            city_finish(s, steps, state),
+            cityhash_rs::cityhash_110_128(&data)
+        );
+    }
+}
+
+//-------------------------------------------------------------
+// SpookyHash
+
+fn spooky_end_partial(h: &mut [u64; 12]) {
+    const ROTATIONS: [u32; 12] = [44, 15, 34, 21, 38, 33, 10, 13, 38, 53, 42, 54];
+    for i in 0..12 {
+        h[(i + 11) % 12] = h[(i + 11) % 12].wrapping_add(h[(i + 1) % 12]);
+        h[(i + 2) % 12] ^= h[(i + 11) % 12];
+        h[(i + 1) % 12] = h[(i + 1) % 12].rotate_left(ROTATIONS[i]);
+    }
+}
+
+/// Messages of 192 bytes and up (the long-message path), hashed by Bob
+/// Jenkins' reference `SpookyHash::Hash128()` and finished by hand from the
+/// absorber.  The message is Jenkins' test message, `buf[i] = i + 128`.
+///
+/// The reference implementation available to generate these is the one that
+/// ships with SMHasher, which is SpookyHash v1.  v1 and v2 share `Mix()`,
+/// which is what the absorber implements; they differ only in the short-input
+/// path and in how the final partial block goes in: v1 absorbs it with
+/// another `Mix()`, where v2 adds it into the state in `End()`.
+#[test]
+fn spookyhash2() {
+    use mixers::spookyhash2::*;
+    const SC_CONST: u64 = 0xdeadbeefdeadbeef;
+    #[rustfmt::skip]
+    const VECTORS: &[(usize, u64, u64, u64, u64)] = &[
+        (192, 0, 0, 0xf3554db4f77f3ae5, 0xf73c0e23da49e8f4),
+        (200, 0, 0, 0x172823429f99ebdd, 0xa7a6b763ec0671e2),
+        (287, 0, 0, 0x0891ffbff5b8f16f, 0x95b510ebf250b621),
+        (288, 0, 0, 0x185a35d432146d34, 0x0c3334348bba1988),
+        (384, 0, 0, 0xff7409e6b4888e7f, 0x896065e17464db3e),
+        (511, 0, 0, 0xbfdecbb9939bd79c, 0xc8cad6894a312ca4),
+        (192, 0x0123456789abcdef, 0xfedcba9876543210, 0x0e26347bc8dfcad7, 0x1f3da69db56288b3),
+        (200, 0x0123456789abcdef, 0xfedcba9876543210, 0xb40049471319c835, 0x8927125c52d0773b),
+        (287, 0x0123456789abcdef, 0xfedcba9876543210, 0x9d4bc2c8460921ce, 0x0f604d446a137e7e),
+        (288, 0x0123456789abcdef, 0xfedcba9876543210, 0x3e2716a7f102c0be, 0xe5257728f4179073),
+        (384, 0x0123456789abcdef, 0xfedcba9876543210, 0x05e0a39e0cd45b2a, 0x4f3ecdc5edc693d3),
+        (511, 0x0123456789abcdef, 0xfedcba9876543210, 0x10de260338dc1c28, 0xd67256312af86671),
+    ];
+
+    for &(len, seed_1, seed_2, hash_1, hash_2) in VECTORS {
+        let message: Vec<u8> = (0..len).map(|i| (i + 128) as u8).collect();
+
+        // The whole blocks, then the rest zero-padded to a block with its
+        // length in the last byte.
+        let blocks = len / IN_SIZE_BYTES;
+        let remainder = len % IN_SIZE_BYTES;
+        let mut data = zero_padded(&message, (blocks + 1) * IN_SIZE_BYTES);
+        *data.last_mut().unwrap() = remainder as u8;
+
+        let state: Vec<u8> = (0..12)
+            .map(|i| [seed_1, seed_2, SC_CONST][i % 3])
+            .flat_map(|word: u64| word.to_le_bytes())
+            .collect();
+        let mut out = vec![0u8; OUT_SIZE_BYTES];
+        mix_input_from_state(&state, &data, &mut out, blocks + 1);
+
+        let mut h: [u64; 12] = u64s(&out).try_into().unwrap();
+        for _ in 0..3 {
+            spooky_end_partial(&mut h);
+        }
+        assert_eq!((h[0], h[1]), (hash_1, hash_2), "length {}", len);
+    }
+}
+
+//-------------------------------------------------------------
+// SipHash
+
+/// A single 8-byte message hashed by std's SipHash-2-4, finished by hand from
+/// the absorber and finalization mixers.
+#[test]
+#[allow(deprecated)]
+fn siphash() {
+    use mixers::siphash::*;
+    use std::hash::{Hasher, SipHasher};
+    const KEY: [u64; 2] = [0x3a5e2c1bd8a7f04e, 0x91c46b0d5f2e7a38];
+
+    let mut rng = WyRand::new_seed(0x4dd19917b03fb552);
+    for _ in 0..ITERATIONS {
+        let word = random_bytes(&mut rng, ABSORB_IN_SIZE_BYTES);
+        let mut v = u64s(&mix(absorb_input::<2>, &word, OUT_SIZE_BYTES, 1));
+
+        // The final word is just the length in the top byte, since the
+        // message has no trailing bytes.
+        let last = (ABSORB_IN_SIZE_BYTES as u64) << 56;
+        v[3] ^= last;
+        let state: Vec<u8> = v.iter().flat_map(|w| w.to_le_bytes()).collect();
+        let mut v = u64s(&mix(mix_input, &state, OUT_SIZE_BYTES, 2));
+        v[0] ^= last;
+
+        v[2] ^= 0xff;
+        let state: Vec<u8> = v.iter().flat_map(|w| w.to_le_bytes()).collect();
+        let v = u64s(&mix(mix_input, &state, OUT_SIZE_BYTES, DEFAULT_ROUNDS));
+
+        let mut hasher = SipHasher::new_with_keys(KEY[0], KEY[1]);
+        hasher.write(&word);
+        assert_eq!(v[0] ^ v[1] ^ v[2] ^ v[3], hasher.finish());
+    }
+}
+
+//-------------------------------------------------------------
+// ChaCha and Salsa20
+
+fn u32s_to_bytes(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|w| w.to_le_bytes()).collect()
+}
+
+fn u32s(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks(4)
+        .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+        .collect()
+}
+
+/// The ChaCha20 block function test vector from RFC 7539 section 2.3.2.
+#[test]
+fn chacha() {
+    use mixers::chacha::*;
+
+    #[rustfmt::skip]
+    let state: [u32; 16] = [
+        0x61707865, 0x3320646e, 0x79622d32, 0x6b206574,
+        0x03020100, 0x07060504, 0x0b0a0908, 0x0f0e0d0c,
+        0x13121110, 0x17161514, 0x1b1a1918, 0x1f1e1d1c,
+        0x00000001, 0x09000000, 0x4a000000, 0x00000000,
+    ];
+    #[rustfmt::skip]
+    let expected: [u32; 16] = [
+        0xe4e7f110, 0x15593bd1, 0x1fdd0f50, 0xc47120a3,
+        0xc7f4d1c7, 0x0368c033, 0x9aaa2204, 0x4e6cd4c3,
+        0x466482d2, 0x09aa9f07, 0x05d7c214, 0xa2028bd9,
+        0xd19c12b5, 0xb94e16de, 0xe883d0cb, 0x4e3c50a2,
+    ];
+
+    let out = u32s(&mix(
+        mix_input,
+        &u32s_to_bytes(&state),
+        OUT_SIZE_BYTES,
+        DEFAULT_ROUNDS,
+    ));
+    let block: Vec<u32> = out
+        .iter()
+        .zip(state.iter())
+        .map(|(a, b)| a.wrapping_add(*b))
+        .collect();
+    assert_eq!(block, expected);
+}
+
+/// The doubleround example from the Salsa20 specification.
+#[test]
+fn salsa20() {
+    use mixers::salsa20::*;
+
+    let mut state = [0u32; 16];
+    state[0] = 1;
+    #[rustfmt::skip]
+    let expected: [u32; 16] = [
+        0x8186a22d, 0x0040a284, 0x82479210, 0x06929051,
+        0x08000090, 0x02402200, 0x00004000, 0x00800000,
+        0x00010200, 0x20400000, 0x08008104, 0x00000000,
+        0x20500000, 0xa0000040, 0x0008180a, 0x612a8020,
+    ];
+
+    let out = u32s(&mix(mix_input, &u32s_to_bytes(&state), OUT_SIZE_BYTES, 1));
+    assert_eq!(out, expected);
+}
+
+//-------------------------------------------------------------
+// BLAKE2b and BLAKE3
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// BLAKE2b-512 of "abc", from RFC 7693 Appendix A.
+#[test]
+fn blake2b() {
+    use mixers::blake2b::*;
+
+    let mut block = [0u64; 16];
+    block[0] = u64::from_le_bytes(*b"abc\0\0\0\0\0");
+    let mut h = [
+        0x6a09e667f3bcc908 ^ 0x01010040,
+        0xbb67ae8584caa73b,
+        0x3c6ef372fe94f82b,
+        0xa54ff53a5f1d36f1,
+        0x510e527fade682d1,
+        0x9b05688c2b3e6c1f,
+        0x1f83d9abfb41bd6b,
+        0x5be0cd19137e2179,
+    ];
+    compress(&mut h, &block, 3, true, DEFAULT_ROUNDS);
+
+    let digest: Vec<u8> = h.iter().flat_map(|w| w.to_le_bytes()).collect();
+    assert_eq!(
+        hex(&digest),
+        "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+         7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+    );
+}
+
+/// BLAKE3 of the empty input.
+#[test]
+fn blake3() {
+    use mixers::blake3::*;
+
+    let out = compress(
+        &IV,
+        &[0; 16],
+        0,
+        0,
+        CHUNK_START | CHUNK_END | ROOT,
+        DEFAULT_ROUNDS,
+    );
+    assert_eq!(
+        hex(&u32s_to_bytes(&out[0..8])),
+        "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+    );
+}
+
+//-------------------------------------------------------------
+// Finalizers
+
+/// Murmur3 of the empty input is just `fmix64()` on the seed.
+#[test]
+fn fmix64() {
+    use finalizers::fmix64;
+
+    let mut rng = WyRand::new_seed(0x2b16be587d47a1fc);
+    for _ in 0..ITERATIONS {
+        let seed = rng.generate::<u32>();
+        let h1 = fmix64(seed as u64 * 2);
+        let h2 = fmix64(seed as u64 * 3);
+        let h1 = h1.wrapping_add(h2);
+        let h2 = h2.wrapping_add(h1);
+        let expected = ::murmur3::murmur3_x64_128(&mut &[][..], seed).unwrap();
+        assert_eq!(((h2 as u128) << 64) | h1 as u128, expected);
+    }
+}
+
+/// xxHash64 of the empty input is just the avalanche on the seed plus a prime.
+#[test]
+fn xxh64_avalanche() {
+    let mut rng = WyRand::new_seed(0x8ff8b8d17ad031ce);
+    for _ in 0..ITERATIONS {
+        let seed = rng.generate::<u64>();
+        let h = finalizers::xxh64_avalanche(seed.wrapping_add(0x27D4EB2F165667C5));
+        assert_eq!(h, xxhash_rust::xxh64::xxh64(&[], seed));
+    }
+}
+
+//-------------------------------------------------------------
+// HighwayHash
+
+/// HighwayHash64 of `packets` whole packets, absorbed from `data` padded
+/// with zeros, with the key from the reference test vectors.
+fn reference_highwayhash64(data: &[u8], packets: usize) -> u64 {
+    use mixers::highwayhash::*;
+
+    let key = [
+        0x0706050403020100,
+        0x0f0e0d0c0b0a0908,
+        0x1716151413121110,
+        0x1f1e1d1c1b1a1918,
+    ];
+    let mut state = reset(&key);
+    absorb(&mut state, data, packets);
+
+    // Finalization: four updates with a permutation of `v0`.
+    for _ in 0..4 {
+        let v0 = state[0];
+        let permuted = [2, 3, 0, 1].map(|i| v0[i].rotate_left(32));
+        update(&mut state, &permuted);
+    }
+
+    state
+        .iter()
+        .fold(0u64, |hash, words| hash.wrapping_add(words[0]))
+}
+
+/// HighwayHash64 of the bytes 0, 1, 2, ... for the empty input and one and
+/// two packets, from the reference test vectors.
+#[test]
+fn highwayhash() {
+    let data: Vec<u8> = (0..64).collect();
+    assert_eq!(reference_highwayhash64(&data, 0), 0x907a56de22c26e53);
+    assert_eq!(reference_highwayhash64(&data[..32], 1), 0xa0c964d9ecd580fc);
+    assert_eq!(reference_highwayhash64(&data, 2), 0x75542c5d4cd2a6ff);
+
+    // Packets past the end of the input are zeros.
+    let padded = zero_padded(&data[..32], 64);
+    assert_eq!(
+        reference_highwayhash64(&data[..32], 2),
+        reference_highwayhash64(&padded, 2)
+    );
+}
+
+//-------------------------------------------------------------
+// Threefish
+
+/// The all-zero Threefish-256 test vector from the Skein specification.
+#[test]
+fn threefish() {
+    use mixers::skein::*;
+
+    let mut block = [0u64; 4];
+    threefish_encrypt(&[0; 4], &[0; 2], &mut block, THREEFISH_DEFAULT_ROUNDS);
+
+    let bytes: Vec<u8> = block.iter().flat_map(|w| w.to_le_bytes()).collect();
+    assert_eq!(
+        hex(&bytes),
+        "84da2a1f8beaee947066ae3e3103f1ad536db1f4a1192495116b9f3ce6133fd8"
+    );
+}
+
+/// Skein-256-256's initial chaining value is the UBI of its configuration
+/// block.
+#[test]
+fn skein_256_256_iv() {
+    use mixers::skein::*;
+
+    // "SHA3", version 1, 256-bit output.
+    let config = [0x0000000133414853, 256, 0, 0];
+    // Position 32, first and final block, configuration type.
+    let tweak = [32, (1 << 62) | (1 << 63) | (4 << 56)];
+
+    let mut block = config;
+    threefish_encrypt(&[0; 4], &tweak, &mut block, THREEFISH_DEFAULT_ROUNDS);
+    let chaining_value: Vec<u64> = block
+        .iter()
+        .zip(config.iter())
+        .map(|(a, b)| a ^ b)
+        .collect();
+    assert_eq!(chaining_value, SKEIN_256_256_IV);
+}
+
+//-------------------------------------------------------------
+// Carry-less multiply and CRC32C
+
+#[test]
+fn clmul() {
+    use mixers::clmul::clmul;
+
+    assert_eq!(clmul(0b11, 0b11), 0b101);
+    assert_eq!(clmul(1 << 63, 1 << 63), 1 << 126);
+    assert_eq!(clmul(!0, !0), 0x5555_5555_5555_5555_5555_5555_5555_5555);
+}
+
+/// CRC32C of 32 zero bytes and of 32 0xff bytes, from the iSCSI test vectors
+/// in RFC 3720.
+#[test]
+fn crc32c() {
+    use mixers::crc32c::crc32c_u64;
+
+    for (word, expected) in [(0, 0x8a9136aa), (!0, 0x62a8ab43)] {
+        let mut crc = !0;
+        for _ in 0..4 {
+            crc = crc32c_u64(crc, word);
+        }
+        assert_eq!(!crc, expected);
+    }
+}
+
+//-------------------------------------------------------------
+// wyhash and mum
+
+const WYHASH_SECRET: [u64; 4] = [
+    0x2d358dccaa6c78a5,
+    0x8bb84b93962eacc9,
+    0x4b33a62ed433d4a3,
+    0x4d5a2da51de1aa47,
+];
+
+/// A 64x64->128 multiply, returned as (low, high) halves.
+fn mum(a: u64, b: u64) -> (u64, u64) {
+    let product = a as u128 * b as u128;
+    (product as u64, (product >> 64) as u64)
+}
+
+/// Whole blocks plus a 16-byte tail hashed by rapidhash v1, finished by hand
+/// from the mixer.
+///
+/// rapidhash v1's long-input loop is wyhash's, except for which secret words
+/// each lane uses and for xoring the length into the initial seed.  Both are
+/// parameters of the rapidhash crate, so it's given wyhash's lane secrets
+/// and a seed that cancels out the length.
+#[test]
+fn wyhash() {
+    use mixers::wyhash::*;
+    let secrets = [WYHASH_SECRET[1], WYHASH_SECRET[2], WYHASH_SECRET[3]];
+    let (low, high) = mum(WYHASH_SECRET[0], WYHASH_SECRET[1]);
+    let initial_seed = low ^ high;
+
+    let mut rng = WyRand::new_seed(0x3c8e1f6a0d95b274);
+    for _ in 0..ITERATIONS {
+        for rounds in 1..=3 {
+            let len = IN_SIZE_BYTES * rounds + 16;
+            let data = random_bytes(&mut rng, len);
+
+            let state = u64s(&mix(
+                mix_input,
+                &data[..(IN_SIZE_BYTES * rounds)],
+                OUT_SIZE_BYTES,
+                rounds,
+            ));
+
+            // Fold the lanes together and absorb the tail (the last 16
+            // bytes), as rapidhash does for a tail of at most 16 bytes.
+            let seed = state[0] ^ state[1] ^ state[2];
+            let (a, b) = mum(
+                u64_at(&data, len - 16) ^ secrets[1],
+                u64_at(&data, len - 8) ^ seed,
+            );
+            let (low, high) = mum(a ^ secrets[0] ^ len as u64, b ^ secrets[1]);
+
+            let rapid_secrets = rapidhash::v1::RapidSecrets {
+                seed: initial_seed ^ len as u64,
+                secrets,
+            };
+            assert_eq!(
+                low ^ high,
+                rapidhash::v1::rapidhash_v1_seeded(&data, &rapid_secrets)
+            );
+        }
+    }
+}
+
+/// Inputs chosen so that, after xoring with the secret words, the product is
+/// easy to work out by hand.
+#[test]
+fn mum_folded_multiply() {
+    use mixers::mum::*;
+    let [s0, s1, ..] = WYHASH_SECRET;
+    let input = |a: u64, b: u64| [a.to_le_bytes(), b.to_le_bytes()].concat();
+
+    // 2 * 3 = 6, with a zero high half.
+    let out = mix(mix_input, &input(s0 ^ 2, s1 ^ 3), OUT_SIZE_BYTES, 1);
+    assert_eq!(u64_at(&out, 0), 6);
+
+    // 2^63 * 2 = 2^64, which is all high half.
+    let out = mix(mix_input, &input(s0 ^ (1 << 63), s1 ^ 2), OUT_SIZE_BYTES, 1);
+    assert_eq!(u64_at(&out, 0), 1);
+
+    // (2^64 - 1)^2 = 2^128 - 2^65 + 1, so the high half is 2^64 - 2 and the
+    // low half is 1.
+    let out = mix(mix_input, &input(!s0, !s1), OUT_SIZE_BYTES, 1);
+    assert_eq!(u64_at(&out, 0), !0);
+
+    // A zero first product leaves the secret words alone for the second
+    // multiply.
+    let (low, high) = mum(s0, s1);
+    let out = mix(mix_input, &input(s0, s1 ^ 5), OUT_SIZE_BYTES, 2);
+    assert_eq!(u64_at(&out, 0), low ^ high);
+}
+
+//-------------------------------------------------------------
+// Keccak-f and Ascon
+
+/// Keccak-f[1600] of the all-zero state, from the Keccak team's intermediate
+/// values.
+#[test]
+fn keccak_f1600() {
+    let out = mix(
+        mixers::keccak::mix_input,
+        &[0; mixers::keccak::IN_SIZE_BYTES],
+        mixers::keccak::OUT_SIZE_BYTES,
+        mixers::keccak::DEFAULT_ROUNDS,
+    );
+
+    assert_eq!(u64_at(&out, 0), 0xf1258f7940e1dde7);
+    assert_eq!(u64_at(&out, 8), 0x84d5ccf933c0478a);
+}
+
+/// Keccak-f[400] of the all-zero state, and of that result, from XKCP's
+/// KeccakF-400-IntermediateValues.txt.
+#[test]
+fn keccak_f400() {
+    use mixers::keccak::*;
+    #[rustfmt::skip]
+    const FIRST: [u16; 25] = [
+        0x09f5, 0x40ac, 0x0fa9, 0x14f5, 0xe89f, 0xeca0, 0x5bd1, 0x7870, 0xeff0, 0xbf8f, 0x0337,
+        0x6052, 0xdc75, 0x0ec9, 0xe776, 0x5246, 0x59a1, 0x5d81, 0x6d95, 0x6e14, 0x633e, 0x58ee,
+        0x71ff, 0x714c, 0xb38e,
+    ];
+    #[rustfmt::skip]
+    const SECOND: [u16; 25] = [
+        0xe537, 0xd5d6, 0xdbe7, 0xaaf3, 0x9bc7, 0xca7d, 0x86b2, 0xfdec, 0x692c, 0x4e5b, 0x67b1,
+        0x15ad, 0xa7f7, 0xa66f, 0x67ff, 0x3f8a, 0x2f99, 0xe2c2, 0x656b, 0x5f31, 0x5ba6, 0xca29,
+        0xc224, 0xb85c, 0x097c,
+    ];
+    let lanes = |bytes: &[u8]| -> Vec<u16> {
+        bytes
+            .chunks(2)
+            .map(|lane| u16::from_le_bytes(lane.try_into().unwrap()))
+            .collect()
+    };
+
+    let first = mix(
+        mix_input_400,
+        &[0; IN_SIZE_BYTES_400],
+        OUT_SIZE_BYTES_400,
+        DEFAULT_ROUNDS_400,
+    );
+    assert_eq!(lanes(&first), FIRST);
+
+    let second = mix(
+        mix_input_400,
+        &first,
+        OUT_SIZE_BYTES_400,
+        DEFAULT_ROUNDS_400,
+    );
+    assert_eq!(lanes(&second), SECOND);
+}
+
+/// Ascon-Hash's initial state, which is p^a of its IV, from the reference
+/// implementation's precomputed values.
+#[test]
+fn ascon() {
+    let mut state = [0x00400c0000000100, 0, 0, 0, 0];
+    mixers::ascon::permute(&mut state, mixers::ascon::DEFAULT_ROUNDS);
+
+    assert_eq!(
+        state,
+        [
+            0xee9398aadb67f03d,
+            0x8bb21831c60f1002,
+            0xb48a92db98d5da62,
+            0x43189921b8f8e3e8,
+            0x348fa5c9d525e140,
+        ]
+    );
+}
@@ -0,0 +1,52 @@
+//! Checks of the input pattern registry.
+
+use lib::patterns::{self, PATTERNS};
+
+#[test]
+fn names_are_unique() {
+    for (i, a) in PATTERNS.iter().enumerate() {
+        for b in PATTERNS[(i + 1)..].iter() {
+            assert!(!a.is_named(b.name), "duplicate pattern `{}`", a.name);
+        }
+    }
+}
+
+#[test]
+fn find() {
+    for pattern in PATTERNS.iter() {
+        assert_eq!(patterns::find(pattern.name).unwrap().name, pattern.name);
+    }
+    assert_eq!(patterns::find("Gray-Code").unwrap().name, "gray code");
+    assert_eq!(patterns::find("single_bit").unwrap().name, "single-bit");
+    assert!(patterns::find("gray").is_none());
+}
+
+/// The high counter fills the last 8 bytes, or all of a shorter input.
+#[test]
+fn high_counting() {
+    let pattern = patterns::find("high counting").unwrap();
+
+    let mut bytes = [0xaau8; 24];
+    (pattern.gen_function)(0x0102_0304_0506, &mut bytes);
+    assert_eq!(bytes[..16], [0; 16]);
+    assert_eq!(bytes[16..], 0x0102_0304_0506u64.to_le_bytes());
+
+    let mut bytes = [0xaau8; 4];
+    (pattern.gen_function)(0x0102_0304_0506, &mut bytes);
+    assert_eq!(bytes, [0x06, 0x05, 0x04, 0x03]);
+}
+
+#[test]
+fn deterministic() {
+    for pattern in PATTERNS.iter() {
+        for size in [8, 16, 64] {
+            for seed in 0..100 {
+                let mut a = vec![0u8; size];
+                let mut b = vec![0xffu8; size];
+                (pattern.gen_function)(seed, &mut a);
+                (pattern.gen_function)(seed, &mut b);
+                assert_eq!(a, b, "pattern `{}`", pattern.name);
+            }
+        }
+    }
+}
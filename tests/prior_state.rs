@@ -0,0 +1,94 @@
+//! Checks that the accumulators' `mix_input_from_state()` picks up exactly
+//! where `mix_input()` leaves off: absorbing some blocks and then the rest
+//! from the resulting state is the same as absorbing all of them at once.
+
+use lib::mixers::{metrohash128, murmur3, spookyhash2, xxhash3};
+use nanorand::{Rng, WyRand};
+
+/// Takes the input, the output, and the number of blocks.
+type MixFunction = fn(&[u8], &mut [u8], usize);
+
+/// Takes a prior state, the input, the output, and the number of blocks.
+type MixFromStateFunction = fn(&[u8], &[u8], &mut [u8], usize);
+
+fn check_continuation(
+    mix_input: MixFunction,
+    mix_input_from_state: MixFromStateFunction,
+    block_size: usize,
+    state_size: usize,
+    first_blocks_multiple: usize,
+    seed: u64,
+) {
+    let mut rng = WyRand::new_seed(seed);
+    for _ in 0..100 {
+        let first_blocks = first_blocks_multiple * rng.generate_range(1..=3usize);
+        let second_blocks = rng.generate_range(1..=3usize);
+        let mut message = vec![0u8; block_size * (first_blocks + second_blocks)];
+        rng.fill_bytes(&mut message);
+        let (first, second) = message.split_at(block_size * first_blocks);
+
+        let mut expected = vec![0u8; state_size];
+        mix_input(&message, &mut expected, first_blocks + second_blocks);
+
+        let mut state = vec![0u8; state_size];
+        mix_input(first, &mut state, first_blocks);
+        let mut out = vec![0u8; state_size];
+        mix_input_from_state(&state, second, &mut out, second_blocks);
+
+        assert_eq!(out, expected);
+    }
+}
+
+#[test]
+fn metrohash128() {
+    use metrohash128::*;
+    check_continuation(
+        mix_input,
+        mix_input_from_state,
+        IN_SIZE_BYTES,
+        OUT_SIZE_BYTES,
+        1,
+        0x8d2f5e0c93b1a647,
+    );
+}
+
+#[test]
+fn murmur3() {
+    use murmur3::*;
+    check_continuation(
+        mix_input,
+        mix_input_from_state,
+        IN_SIZE_BYTES,
+        OUT_SIZE_BYTES,
+        1,
+        0x51c7e0a9b4f2d386,
+    );
+}
+
+#[test]
+fn spookyhash2() {
+    use spookyhash2::*;
+    check_continuation(
+        mix_input,
+        mix_input_from_state,
+        IN_SIZE_BYTES,
+        OUT_SIZE_BYTES,
+        1,
+        0xe63a1d95c70b28f4,
+    );
+}
+
+/// The secret offset depends on the stripe's position in its block, so the
+/// state only continues cleanly from a block boundary.
+#[test]
+fn xxhash3() {
+    use xxhash3::*;
+    check_continuation(
+        mix_input,
+        mix_input_from_state,
+        IN_SIZE_BYTES,
+        OUT_SIZE_BYTES,
+        STRIPES_PER_BLOCK,
+        0x2b94f6c80d3e71a5,
+    );
+}
@@ -0,0 +1,182 @@
+//! Checks of the statistics computed from avalanche charts.
+
+use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher};
+
+use lib::mixers::{clmul, crc32c, finalizers, fnv1a};
+use lib::stats::{compute_chaining_stats, compute_derivative_bias, Stats};
+use nanorand::{Rng, WyRand};
+
+/// A chart with random flip counts, as if from `samples` samples.
+fn random_chart(in_bits: usize, out_bits: usize, samples: u32, seed: u64) -> Stats {
+    let mut stats = Stats::new(in_bits, out_bits, out_bits, true, false);
+    let mut rng = WyRand::new_seed(seed);
+    for in_bit in 0..in_bits {
+        for out_bit in 0..out_bits {
+            for _ in 0..rng.generate_range(0..=samples) {
+                stats.accumulate(in_bit, out_bit, true);
+            }
+        }
+    }
+    stats.sample_count = samples as usize;
+    stats
+}
+
+#[test]
+fn lane_bias_one_lane() {
+    let stats = random_chart(24, 40, 100, 0x3d1f6a2b9e8c0475);
+
+    // A lane at least as wide as the input and output covers the whole chart.
+    for lane_bits in [40, 64] {
+        let (avg, max) = stats.lane_bias(0, 0, lane_bits);
+        assert!((avg - stats.average_bias()).abs() < 1e-12);
+        assert_eq!(max, stats.max_bias());
+    }
+}
+
+#[test]
+fn lane_bias_lanes() {
+    // Input lane 0 flips output lane 1 every time and output lane 0 half the
+    // time, and input lane 1 flips nothing.
+    let mut stats = Stats::new(16, 16, 16, true, false);
+    stats.sample_count = 10;
+    for in_bit in 0..8 {
+        for out_bit in 0..16 {
+            let flips = if out_bit < 8 { 5 } else { 10 };
+            for _ in 0..flips {
+                stats.accumulate(in_bit, out_bit, true);
+            }
+        }
+    }
+
+    assert_eq!(stats.lane_bias(0, 0, 8), (0.0, 0.0));
+    assert_eq!(stats.lane_bias(0, 1, 8), (1.0, 1.0));
+    assert_eq!(stats.lane_bias(1, 0, 8), (1.0, 1.0));
+    assert_eq!(stats.lane_bias(1, 1, 8), (1.0, 1.0));
+
+    // Lanes that don't line up with the flips, and a narrower last lane.
+    let (avg, max) = stats.lane_bias(0, 0, 12);
+    assert!((avg - 80.0 / 144.0).abs() < 1e-12);
+    assert_eq!(max, 1.0);
+    assert_eq!(stats.lane_bias(1, 0, 12), (1.0, 1.0));
+}
+
+#[test]
+#[should_panic]
+fn lane_bias_no_samples() {
+    Stats::new(8, 8, 8, true, false).lane_bias(0, 0, 8);
+}
+
+/// The 2nd and 3rd-order derivative biases of a mixer.
+fn derivative_biases(
+    mix_input: fn(&[u8], &mut [u8], usize),
+    input_size: usize,
+    output_size: usize,
+    rounds: usize,
+) -> [Vec<f64>; 2] {
+    let mix = |input: &[u8], output: &mut [u8]| mix_input(input, output, rounds);
+    [2, 3].map(|order| compute_derivative_bias(mix, input_size, output_size, order, 2000))
+}
+
+fn average(biases: &[f64]) -> f64 {
+    biases.iter().sum::<f64>() / biases.len() as f64
+}
+
+#[test]
+fn derivative_bias_crc32c() {
+    use crc32c::*;
+    let [order_2, order_3] =
+        derivative_biases(mix_input, IN_SIZE_BYTES, OUT_SIZE_BYTES, DEFAULT_ROUNDS);
+
+    // A CRC is affine.
+    assert!(order_2.iter().all(|&bias| bias == 1.0));
+    assert!(order_3.iter().all(|&bias| bias == 1.0));
+}
+
+#[test]
+fn derivative_bias_clmul() {
+    use clmul::*;
+    let [order_2, order_3] =
+        derivative_biases(mix_input, IN_SIZE_BYTES, OUT_SIZE_BYTES, DEFAULT_ROUNDS);
+
+    // A single carry-less multiply is quadratic but not affine.
+    assert!(average(&order_2) < 0.1);
+    assert!(order_3.iter().all(|&bias| bias == 1.0));
+}
+
+#[test]
+fn derivative_bias_fmix64() {
+    use finalizers::*;
+    let [order_2, order_3] =
+        derivative_biases(mix_fmix64, IN_SIZE_BYTES, OUT_SIZE_BYTES, DEFAULT_ROUNDS);
+
+    // A good finalizer's derivatives look random.
+    assert!(order_2.iter().all(|&bias| bias < 0.15));
+    assert!(order_3.iter().all(|&bias| bias < 0.15));
+}
+
+/// An accumulator whose state is just the last block it absorbed, so a
+/// difference in any earlier block never reaches the state.
+#[test]
+fn chaining_stats_overwritten_block() {
+    let last_block = |message: &[u8], state: &mut [u8]| {
+        state.copy_from_slice(&message[(message.len() - 8)..]);
+    };
+
+    for following_blocks in 1..3 {
+        let stats = compute_chaining_stats(last_block, 8, 8, 8, 0, following_blocks, 50);
+        assert_eq!(stats.sample_count, 50);
+        assert!(stats.avalanche_chart.iter().all(|&flips| flips == 0));
+        assert_eq!(stats.max_input_bit_diffusion(), 0.0);
+    }
+
+    // With no blocks after it, each input bit flips only its own state bit.
+    let stats = compute_chaining_stats(last_block, 8, 8, 8, 1, 0, 50);
+    for in_bit in 0..64 {
+        for out_bit in 0..64 {
+            let expected = if in_bit == out_bit { 50 } else { 0 };
+            assert_eq!(stats.get(in_bit, out_bit), expected);
+        }
+    }
+}
+
+/// An accumulator whose state is random bits seeded by a strong hash of the
+/// whole message, so any difference gives an unrelated state.
+#[test]
+fn chaining_stats_random_oracle() {
+    let random_oracle = |message: &[u8], state: &mut [u8]| {
+        let seed = BuildHasherDefault::<DefaultHasher>::default().hash_one(message);
+        WyRand::new_seed(seed).fill_bytes(state);
+    };
+
+    for (diff_block, following_blocks) in [(0, 0), (0, 2), (2, 1)] {
+        let stats =
+            compute_chaining_stats(random_oracle, 4, 16, 8, diff_block, following_blocks, 2000);
+        assert!(stats.max_bias() < 0.15);
+        assert!(stats.min_input_bit_diffusion() > 120.0);
+    }
+}
+
+/// FNV-1a ends each byte with a multiply, which only carries upwards, so a
+/// difference in bit `j` of the last byte absorbed always flips state bit
+/// `j` and never the bits below it.
+#[test]
+fn chaining_stats_fnv1a_last_byte() {
+    use fnv1a::*;
+    let stats = compute_chaining_stats(
+        |message: &[u8], state: &mut [u8]| mix_input(message, state, message.len() / IN_SIZE_BYTES),
+        IN_SIZE_BYTES,
+        OUT_SIZE_BYTES,
+        DIGEST_SIZE_BYTES,
+        1,
+        0,
+        200,
+    );
+
+    let last_byte = (IN_SIZE_BYTES - 1) * 8;
+    for j in 0..8 {
+        assert_eq!(stats.get(last_byte + j, j), 200);
+        for out_bit in 0..j {
+            assert_eq!(stats.get(last_byte + j, out_bit), 0);
+        }
+    }
+}
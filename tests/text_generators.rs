@@ -0,0 +1,73 @@
+//! Checks of the text input generators' formats.
+
+use lib::stats::{
+    generate_decimal, generate_hex_counter, generate_lowercase_words, generate_uuid_v4,
+};
+
+/// Runs a generator and returns the text it wrote, checking that the rest of
+/// the input is zeros.
+fn text(generate: fn(usize, &mut [u8]), seed: usize, len: usize) -> String {
+    let mut bytes = vec![0xaau8; len];
+    generate(seed, &mut bytes);
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(len);
+    assert!(bytes[end..].iter().all(|&b| b == 0));
+    assert!(bytes[..end].iter().all(|b| b.is_ascii_graphic()));
+    String::from_utf8(bytes[..end].to_vec()).unwrap()
+}
+
+#[test]
+fn decimal() {
+    assert_eq!(text(generate_decimal, 0, 16), "0");
+    assert_eq!(text(generate_decimal, 1234, 16), "1234");
+    assert_eq!(text(generate_decimal, 123456, 4), "1234");
+}
+
+#[test]
+fn hex_counter() {
+    assert_eq!(text(generate_hex_counter, 1234, 32), "00000000000004d2");
+    assert_eq!(text(generate_hex_counter, 1234, 8), "00000000");
+}
+
+#[test]
+fn lowercase_words() {
+    for seed in 0..1000 {
+        let words = text(generate_lowercase_words, seed, 64);
+        assert_eq!(words, text(generate_lowercase_words, seed, 64));
+        let words: Vec<&str> = words.split('_').collect();
+        assert!((1..=3).contains(&words.len()));
+        for word in words {
+            assert!((2..=8).contains(&word.len()));
+            assert!(word.bytes().all(|b| (b'a'..=b'h').contains(&b)));
+        }
+    }
+}
+
+#[test]
+fn uuid_v4() {
+    let mut uuids = Vec::new();
+    for seed in 0..1000 {
+        let uuid = text(generate_uuid_v4, seed, 64);
+        assert_eq!(uuid, text(generate_uuid_v4, seed, 64));
+
+        let groups: Vec<&str> = uuid.split('-').collect();
+        assert_eq!(
+            groups.iter().map(|g| g.len()).collect::<Vec<_>>(),
+            [8, 4, 4, 4, 12]
+        );
+        assert!(uuid
+            .bytes()
+            .all(|b| b == b'-' || b.is_ascii_digit() || (b'a'..=b'f').contains(&b)));
+        assert!(groups[2].starts_with('4'));
+        assert!("89ab".contains(&groups[3][..1]));
+        uuids.push(uuid);
+    }
+    uuids.sort();
+    uuids.dedup();
+    assert_eq!(uuids.len(), 1000);
+
+    // Truncated to fit.
+    assert_eq!(
+        text(generate_uuid_v4, 0, 8),
+        text(generate_uuid_v4, 0, 64)[..8]
+    );
+}